@@ -0,0 +1,405 @@
+//! Archives of the whole app state, to move it to another machine or keep a
+//! copy: the todos, the settings with their lists and labels, and metadata
+//! telling which version of the app made the archive, when and from which
+//! data file.
+//!
+//! `ARCHIVE_VERSION` changes with the layout and `read_archive` upgrades older
+//! archives. A plain `todos.json` is read as version 0.
+//!
+//! Script hooks, webhooks and the HTTP API settings are left out of archives
+//! and stay local on import: an archive is meant to be moved around, and one
+//! received from someone else must not install executables or open the API
+//! with a token they know.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::commands::{lock_error, persist_state};
+use crate::merge::{self, MergeReport, MergeStrategy};
+use crate::storage::{
+    app_data_dir, normalize_data, now_millis, AppData, AppState, HttpApiSettings, Settings,
+    STORAGE_FILE_NAME,
+};
+use crate::{http_api, shortcuts, tray};
+
+pub const ARCHIVE_FORMAT: &str = "blinkdo-archive";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub metadata: ArchiveMetadata,
+    pub data: AppData,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveMetadata {
+    pub app_version: String,
+    pub exported_at: i64,
+    /// Operating system of the exporting machine, as in `std::env::consts::OS`.
+    pub platform: String,
+    /// Data file the archive was made from, and when it was last saved.
+    pub data_file: Option<String>,
+    pub data_modified_at: Option<i64>,
+    pub todos: usize,
+    pub lists: usize,
+    pub labels: usize,
+}
+
+/// Outcome of `import_archive`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImport {
+    /// Version the archive was written in, 0 for a plain data file.
+    pub version: u32,
+    /// `None` for a plain data file.
+    pub metadata: Option<ArchiveMetadata>,
+    pub dry_run: bool,
+    /// `None` when the archive replaced the data.
+    pub strategy: Option<MergeStrategy>,
+    #[serde(flatten)]
+    pub changes: MergeReport,
+    /// Records that were repaired or dropped while validating.
+    pub warnings: Vec<String>,
+}
+
+/// Writes an archive of the current state to the absolute `path`, replacing
+/// it if it exists.
+#[tauri::command]
+pub fn export_archive(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ArchiveMetadata, String> {
+    let path = Path::new(&path);
+    if !path.is_absolute() {
+        return Err(format!("archive path must be absolute: {}", path.display()));
+    }
+
+    let data_file = app_data_dir(&app)?.join(STORAGE_FILE_NAME);
+    let data_modified_at = fs::metadata(&data_file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as i64);
+
+    let archive = {
+        let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+        to_archive(
+            &data,
+            Some(data_file.display().to_string()),
+            data_modified_at,
+            now_millis(),
+        )
+    };
+    let payload = serde_json::to_string_pretty(&archive)
+        .map_err(|error| format!("failed to serialize archive: {error}"))?;
+
+    fs::write(path, payload)
+        .map_err(|error| format!("failed to write archive {}: {error}", path.display()))?;
+    log::info!(
+        "exported an archive of {} todos to {}",
+        archive.metadata.todos,
+        path.display()
+    );
+    Ok(archive.metadata)
+}
+
+/// Reads the archive at `path` and replaces the current state with it, or
+/// with a `merge` strategy merges its records into the current state by id.
+/// With `dry_run` nothing is saved and the report tells what would change.
+#[tauri::command]
+pub fn import_archive(
+    path: String,
+    merge: Option<MergeStrategy>,
+    dry_run: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ArchiveImport, String> {
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read archive {path}: {error}"))?;
+    let (incoming, mut report) = read_archive(&content)?;
+    report.dry_run = dry_run;
+    report.strategy = merge;
+
+    let previous;
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        previous = guard.settings.clone();
+        let next = match merge {
+            Some(strategy) => {
+                let mut merged = guard.clone();
+                report.changes = merge::merge(&mut merged, incoming, strategy);
+                normalize_data(merged)
+            }
+            None => {
+                let mut incoming = incoming;
+                keep_local_integrations(&mut incoming.settings, &guard.settings);
+                report.changes = MergeReport {
+                    lists_added: incoming.settings.lists.len(),
+                    labels_added: incoming.settings.labels.len(),
+                    todos_added: incoming.todos.len(),
+                    ..MergeReport::default()
+                };
+                incoming
+            }
+        };
+        if dry_run {
+            return Ok(report);
+        }
+        *guard = next;
+    }
+
+    log::info!(
+        "imported an archive of version {} from {path} (merge: {merge:?})",
+        report.version
+    );
+    let data = persist_state(&app, &state)?;
+    apply_imported_settings(&app, &previous, &data.settings);
+    Ok(report)
+}
+
+/// Registers the shortcut, tray labels and HTTP API of the imported settings,
+/// as `update_settings` does.
+fn apply_imported_settings(app: &AppHandle, previous: &Settings, settings: &Settings) {
+    if settings.global_shortcut != previous.global_shortcut {
+        if let Err(error) = shortcuts::replace_registered_shortcut(app, &settings.global_shortcut) {
+            log::error!("failed to register the imported shortcut: {error}");
+        }
+    }
+    if settings.language != previous.language {
+        if let Err(error) = tray::refresh_menu(app) {
+            log::error!("failed to rebuild tray menu after import: {error}");
+        }
+    }
+    http_api::apply_settings(app, &settings.http_api);
+}
+
+/// Replaces the integrations of `settings`, which run code or accept
+/// requests, with the `local` ones.
+fn keep_local_integrations(settings: &mut Settings, local: &Settings) {
+    settings.script_hooks = local.script_hooks.clone();
+    settings.webhooks = local.webhooks.clone();
+    settings.http_api = local.http_api.clone();
+}
+
+pub fn to_archive(
+    data: &AppData,
+    data_file: Option<String>,
+    data_modified_at: Option<i64>,
+    now: i64,
+) -> Archive {
+    Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        metadata: ArchiveMetadata {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: now,
+            platform: std::env::consts::OS.to_string(),
+            data_file,
+            data_modified_at,
+            todos: data.todos.len(),
+            lists: data.settings.lists.len(),
+            labels: data.settings.labels.len(),
+        },
+        data: AppData {
+            settings: Settings {
+                script_hooks: Vec::new(),
+                webhooks: Vec::new(),
+                http_api: HttpApiSettings::default(),
+                ..data.settings.clone()
+            },
+            todos: data.todos.clone(),
+            revision: None,
+        },
+    }
+}
+
+/// Validates `content`, upgrades it to the current version and returns its
+/// data with a report of what was repaired.
+pub fn read_archive(content: &str) -> Result<(AppData, ArchiveImport), String> {
+    let value = serde_json::from_str::<Value>(content)
+        .map_err(|error| format!("not a BlinkDo archive: {error}"))?;
+    let Value::Object(mut object) = value else {
+        return Err("not a BlinkDo archive: expected an object".to_string());
+    };
+
+    let mut report = ArchiveImport::default();
+    let data = match object.get("format").and_then(Value::as_str) {
+        // A plain data file, as `get_data_file_path` points at.
+        None if object.contains_key("todos") || object.contains_key("settings") => {
+            Value::Object(object)
+        }
+        Some(ARCHIVE_FORMAT) => {
+            let version = object
+                .get("version")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| "the archive has no version".to_string())?;
+            if version > u64::from(ARCHIVE_VERSION) {
+                let app_version = object
+                    .get("metadata")
+                    .and_then(|metadata| metadata.get("appVersion"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown");
+                return Err(format!(
+                    "the archive was made by a newer version of BlinkDo ({app_version})"
+                ));
+            }
+            let metadata = object
+                .remove("metadata")
+                .map(serde_json::from_value::<ArchiveMetadata>)
+                .transpose()
+                .map_err(|error| format!("invalid archive metadata: {error}"))?;
+
+            report.version = version as u32;
+            report.metadata = metadata;
+            object
+                .remove("data")
+                .ok_or_else(|| "the archive has no data".to_string())?
+        }
+        _ => return Err("not a BlinkDo archive".to_string()),
+    };
+
+    let data = serde_json::from_value::<AppData>(data)
+        .map_err(|error| format!("invalid archive data: {error}"))?;
+    let settings = &data.settings;
+    if !settings.script_hooks.is_empty()
+        || !settings.webhooks.is_empty()
+        || settings.http_api.enabled
+    {
+        report.warnings.push(
+            "the script hooks, webhooks and HTTP API settings of the archive were ignored"
+                .to_string(),
+        );
+    }
+    let data = repair_todos(normalize_data(data), &mut report.warnings);
+    Ok((data, report))
+}
+
+/// Gives todos without an id a new one, drops later todos sharing an id and
+/// detaches subtasks whose parent is missing.
+fn repair_todos(mut data: AppData, warnings: &mut Vec<String>) -> AppData {
+    let mut ids = HashSet::new();
+    data.todos.retain_mut(|todo| {
+        if todo.id.trim().is_empty() {
+            todo.id = uuid::Uuid::new_v4().to_string();
+        }
+        let unique = ids.insert(todo.id.clone());
+        if !unique {
+            warnings.push(format!(
+                "\"{}\" was dropped, its id {} is already used",
+                todo.title, todo.id
+            ));
+        }
+        unique
+    });
+
+    for todo in &mut data.todos {
+        if let Some(parent_id) = todo.parent_id.as_deref() {
+            if parent_id == todo.id || !ids.contains(parent_id) {
+                warnings.push(format!(
+                    "\"{}\" was moved to the top level, its parent {parent_id} is missing",
+                    todo.title
+                ));
+                todo.parent_id = None;
+            }
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Todo;
+
+    fn todo(id: &str, title: &str, parent_id: Option<&str>) -> Todo {
+        Todo {
+            parent_id: parent_id.map(str::to_string),
+            ..Todo::for_test(id, title)
+        }
+    }
+
+    #[test]
+    fn reads_archives_and_merges_by_id() {
+        let source = AppData {
+            todos: vec![
+                todo("a", "Kept", None),
+                todo("a", "Duplicate", None),
+                todo("b", "Orphan", Some("gone")),
+            ],
+            ..AppData::default()
+        };
+        let archive = to_archive(&source, None, None, 5_000);
+        let content = serde_json::to_string(&archive).expect("serializes");
+
+        let (incoming, report) = read_archive(&content).expect("valid archive");
+        assert_eq!(report.version, ARCHIVE_VERSION);
+        assert_eq!(
+            report.metadata.map(|metadata| metadata.exported_at),
+            Some(5_000)
+        );
+        assert_eq!(report.warnings.len(), 2);
+        assert_eq!(incoming.todos.len(), 2);
+        assert_eq!(incoming.todos[1].parent_id, None);
+
+        let mut local = AppData {
+            todos: vec![todo("a", "Old title", None), todo("c", "Local", None)],
+            ..AppData::default()
+        };
+        let changes = merge::merge(&mut local, incoming, MergeStrategy::KeepIncoming);
+        assert_eq!(changes.todos_added, 1);
+        assert_eq!(changes.todos_updated, 1);
+        assert_eq!(local.todos[0].title, "Kept");
+        assert_eq!(local.todos.len(), 3);
+
+        let (plain, report) =
+            read_archive(&serde_json::to_string(&source).expect("serializes")).expect("data file");
+        assert_eq!(report.version, 0);
+        assert_eq!(plain.todos.len(), 2);
+
+        let newer = content.replacen(
+            &format!("\"version\":{ARCHIVE_VERSION}"),
+            "\"version\":99",
+            1,
+        );
+        assert!(read_archive(&newer).is_err());
+        assert!(read_archive("[]").is_err());
+    }
+
+    #[test]
+    fn leaves_integrations_out_of_archives() {
+        let raw = r#"{
+            "settings": {
+                "webhooks": [{ "url": "https://example.com/hook" }],
+                "scriptHooks": [{ "command": "/usr/bin/true", "event": "created" }],
+                "httpApi": { "enabled": true, "port": 4242, "token": "secret" }
+            },
+            "todos": []
+        }"#;
+        let (source, report) = read_archive(raw).expect("data file");
+        assert_eq!(report.warnings.len(), 1);
+
+        let archive = to_archive(&source, None, None, 5_000);
+        assert!(archive.data.settings.webhooks.is_empty());
+        assert!(archive.data.settings.script_hooks.is_empty());
+        assert!(!archive.data.settings.http_api.enabled);
+        assert!(archive.data.settings.http_api.token.is_empty());
+
+        let mut incoming = source.clone();
+        keep_local_integrations(&mut incoming.settings, &AppData::default().settings);
+        assert!(incoming.settings.webhooks.is_empty());
+        assert!(incoming.settings.script_hooks.is_empty());
+        assert!(!incoming.settings.http_api.enabled);
+    }
+}
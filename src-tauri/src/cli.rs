@@ -0,0 +1,768 @@
+//! `blinkdo <command>`: command-line client working on the same `todos.json`
+//! as the app. Changes go through the running app's IPC socket when it is
+//! open, and through the mutation helpers of `commands.rs` otherwise.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::commands::{apply_todo_completed, insert_todo, move_todo_tree, remove_todo_tree};
+use crate::ipc::IpcClient;
+use crate::storage::{
+    self, classify_availability, now_millis, ordered_todos, parse_date_time, resolve_list_id,
+    resolve_todo_id, AppData, DataLock, Todo, TodoAvailability, STORAGE_FILE_NAME,
+};
+
+const USAGE: &str = "\
+Usage: blinkdo <command> [options]
+
+Commands:
+  add <title>           Create a todo
+      --list <list>       Target list (id or name, defaults to the active list)
+      --details <text>    Details
+      --parent <id>       Parent todo
+      --remind <when>     Reminder (YYYY-MM-DD HH:MM, RFC 3339 or Unix millis)
+  ls                    List open todos
+      --list <list>       Only this list
+      --all               Include deferred and completed todos
+  done <id>             Complete a todo and its subtasks
+  rm <id>               Delete a todo and its subtasks
+  mv <id> <list>        Move a todo and its subtasks to another list
+  search <query>        Search titles and details
+      --all               Include completed todos
+  lists                 List the todo lists
+
+Options:
+  --json                Print JSON instead of tables
+
+Run `blinkdo --mcp` to serve the Model Context Protocol on stdin/stdout.
+
+Todo ids may be abbreviated to any unique prefix.";
+
+const SUBCOMMANDS: &[&str] = &["add", "ls", "done", "rm", "mv", "search", "lists", "help"];
+const SHORT_ID_LENGTH: usize = 8;
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Add {
+        title: String,
+        list: Option<String>,
+        details: Option<String>,
+        parent: Option<String>,
+        remind: Option<i64>,
+    },
+    Ls {
+        list: Option<String>,
+        all: bool,
+    },
+    Done {
+        id: String,
+    },
+    Rm {
+        id: String,
+    },
+    Mv {
+        id: String,
+        list: String,
+    },
+    Search {
+        query: String,
+        all: bool,
+    },
+    Lists,
+    Help,
+}
+
+#[derive(Debug)]
+struct Invocation {
+    command: Command,
+    json: bool,
+}
+
+/// Whether the process arguments (without the program name) select the
+/// command-line client rather than the desktop app.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.first().is_some_and(|first| {
+        SUBCOMMANDS.contains(&first.as_str()) || first == "--help" || first == "-h"
+    })
+}
+
+/// Attaches to the console of the calling shell on Windows, where release
+/// builds use the GUI subsystem and would otherwise print nothing.
+pub fn attach_console() {
+    #[cfg(windows)]
+    {
+        const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+        }
+
+        // SAFETY: takes no pointers; fails harmlessly without a parent console.
+        unsafe {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
+
+/// Runs the command-line client and returns the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    match parse(args).and_then(execute) {
+        Ok(()) => 0,
+        Err(CliError::Usage(message)) => {
+            eprintln!("blinkdo: {message}\n\n{USAGE}");
+            2
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("blinkdo: {message}");
+            1
+        }
+    }
+}
+
+fn parse(args: Vec<String>) -> Result<Invocation, CliError> {
+    let mut args = args.into_iter();
+    let name = args.next().unwrap_or_else(|| "help".to_string());
+
+    let mut json = false;
+    let mut all = false;
+    let mut options: HashMap<&'static str, String> = HashMap::new();
+    let mut positionals = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+
+        let key = match flag.as_str() {
+            "--json" => {
+                json = true;
+                continue;
+            }
+            "--all" => {
+                all = true;
+                continue;
+            }
+            "--list" => "list",
+            "--details" => "details",
+            "--parent" => "parent",
+            "--remind" => "remind",
+            _ if flag.starts_with("--") => {
+                return Err(CliError::Usage(format!("unknown option {flag}")));
+            }
+            _ => {
+                positionals.push(arg);
+                continue;
+            }
+        };
+
+        let value = match inline_value {
+            Some(value) => value.to_string(),
+            None => args
+                .next()
+                .ok_or_else(|| CliError::Usage(format!("missing value for {flag}")))?,
+        };
+        options.insert(key, value);
+    }
+
+    let single = |positionals: &[String], what: &str| match positionals {
+        [value] => Ok(value.clone()),
+        _ => Err(CliError::Usage(format!("{name} expects {what}"))),
+    };
+
+    let command = match name.as_str() {
+        "add" => {
+            if positionals.is_empty() {
+                return Err(CliError::Usage("add expects a title".to_string()));
+            }
+            Command::Add {
+                title: positionals.join(" "),
+                list: options.remove("list"),
+                details: options.remove("details"),
+                parent: options.remove("parent"),
+                remind: options
+                    .remove("remind")
+                    .map(|value| parse_when(&value))
+                    .transpose()?,
+            }
+        }
+        "ls" => Command::Ls {
+            list: options.remove("list"),
+            all,
+        },
+        "done" => Command::Done {
+            id: single(&positionals, "a todo id")?,
+        },
+        "rm" => Command::Rm {
+            id: single(&positionals, "a todo id")?,
+        },
+        "mv" => match positionals.as_slice() {
+            [id, list] => Command::Mv {
+                id: id.clone(),
+                list: list.clone(),
+            },
+            _ => {
+                return Err(CliError::Usage(
+                    "mv expects a todo id and a list".to_string(),
+                ))
+            }
+        },
+        "search" => {
+            if positionals.is_empty() {
+                return Err(CliError::Usage("search expects a query".to_string()));
+            }
+            Command::Search {
+                query: positionals.join(" "),
+                all,
+            }
+        }
+        "lists" => Command::Lists,
+        "help" | "--help" | "-h" => Command::Help,
+        other => return Err(CliError::Usage(format!("unknown command {other}"))),
+    };
+
+    if let Some(unused) = options.keys().next() {
+        return Err(CliError::Usage(format!(
+            "--{unused} is not supported by {name}"
+        )));
+    }
+
+    Ok(Invocation { command, json })
+}
+
+fn parse_when(value: &str) -> Result<i64, CliError> {
+    parse_date_time(value).ok_or_else(|| CliError::Usage(format!("invalid date {value}")))
+}
+
+/// A change computed from the current data, applied either in-process or by
+/// the running app.
+pub(crate) enum Change {
+    Add {
+        title: String,
+        details: Option<String>,
+        reminder_at: Option<i64>,
+        parent_id: Option<String>,
+        list_id: Option<String>,
+    },
+    Complete {
+        id: String,
+    },
+    Delete {
+        id: String,
+    },
+    Move {
+        id: String,
+        list_id: String,
+    },
+}
+
+impl Change {
+    pub(crate) fn apply(self, data: &mut AppData) -> Result<(), String> {
+        match self {
+            Self::Add {
+                title,
+                details,
+                reminder_at,
+                parent_id,
+                list_id,
+            } => insert_todo(data, title, details, reminder_at, parent_id, list_id)
+                .map(|_| ())
+                .ok_or_else(|| "the title must not be empty".to_string()),
+            Self::Complete { id } => {
+                apply_todo_completed(data, &id, true);
+                Ok(())
+            }
+            Self::Delete { id } => {
+                remove_todo_tree(data, &id);
+                Ok(())
+            }
+            Self::Move { id, list_id } => move_todo_tree(data, &id, &list_id)
+                .then_some(())
+                .ok_or_else(|| format!("failed to move {id} to {list_id}")),
+        }
+    }
+
+    /// The IPC method and parameters performing the same change.
+    fn request(self) -> (&'static str, Value) {
+        match self {
+            Self::Add {
+                title,
+                details,
+                reminder_at,
+                parent_id,
+                list_id,
+            } => (
+                "create_todo",
+                json!({
+                    "title": title,
+                    "details": details,
+                    "reminderAt": reminder_at,
+                    "parentId": parent_id,
+                    "listId": list_id,
+                }),
+            ),
+            Self::Complete { id } => ("set_todo_completed", json!({ "id": id, "completed": true })),
+            Self::Delete { id } => ("delete_todo", json!({ "id": id })),
+            Self::Move { id, list_id } => {
+                ("move_todo_to_list", json!({ "id": id, "listId": list_id }))
+            }
+        }
+    }
+}
+
+pub(crate) struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub(crate) fn open() -> Result<Self, String> {
+        Ok(Self {
+            dir: storage::standalone_data_dir()?,
+        })
+    }
+
+    pub(crate) fn read(&self) -> Result<AppData, String> {
+        let _lock = DataLock::acquire(&self.dir)?;
+        Ok(storage::read_data_file(&self.dir.join(STORAGE_FILE_NAME))?.unwrap_or_default())
+    }
+
+    /// Computes a change from the current data and applies it, returning the
+    /// data before and after. The file is only written here while the app is
+    /// closed; otherwise the app applies the change so that its in-memory
+    /// state stays authoritative.
+    pub(crate) fn update(
+        &self,
+        plan: impl FnOnce(&AppData) -> Result<Change, String>,
+    ) -> Result<(AppData, AppData), String> {
+        {
+            let _lock = DataLock::acquire(&self.dir)?;
+
+            if !storage::is_app_running(&self.dir)? {
+                let path = self.dir.join(STORAGE_FILE_NAME);
+                let before = storage::read_data_file(&path)?.unwrap_or_default();
+                let mut after = before.clone();
+                plan(&before)?.apply(&mut after)?;
+                storage::write_data_file(&path, &after)?;
+                return Ok((before, after));
+            }
+        }
+
+        // The data lock must be released first: the app takes it to save.
+        let mut client = IpcClient::connect(&self.dir)?;
+        let before = decode_data(client.call("load_state", Value::Null)?)?;
+        let (method, params) = plan(&before)?.request();
+        let after = decode_data(client.call(method, params)?)?;
+        Ok((before, after))
+    }
+}
+
+fn decode_data(value: Value) -> Result<AppData, String> {
+    serde_json::from_value(value).map_err(|error| format!("invalid data from BlinkDo: {error}"))
+}
+
+/// Ids of the todos present in `after` for which `changed` holds.
+pub(crate) fn changed_ids(
+    before: &AppData,
+    after: &AppData,
+    changed: impl Fn(Option<&Todo>, &Todo) -> bool,
+) -> Vec<String> {
+    let previous = before
+        .todos
+        .iter()
+        .map(|todo| (todo.id.as_str(), todo))
+        .collect::<HashMap<_, _>>();
+
+    after
+        .todos
+        .iter()
+        .filter(|todo| changed(previous.get(todo.id.as_str()).copied(), todo))
+        .map(|todo| todo.id.clone())
+        .collect()
+}
+
+fn execute(invocation: Invocation) -> Result<(), CliError> {
+    let json = invocation.json;
+
+    if let Command::Help = invocation.command {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let store = Store::open()?;
+
+    match invocation.command {
+        Command::Add {
+            title,
+            list,
+            details,
+            parent,
+            remind,
+        } => {
+            let (before, after) = store.update(|data| {
+                let list_id = list
+                    .as_deref()
+                    .map(|list| resolve_list_id(data, list))
+                    .transpose()?;
+                let parent_id = parent
+                    .as_deref()
+                    .map(|parent| resolve_todo_id(data, parent))
+                    .transpose()?;
+
+                Ok(Change::Add {
+                    title,
+                    details,
+                    reminder_at: remind,
+                    parent_id,
+                    list_id,
+                })
+            })?;
+
+            let added = changed_ids(&before, &after, |previous, _| previous.is_none());
+            let todo = added
+                .first()
+                .map(|id| find_todo(&after, id))
+                .ok_or_else(|| "the todo was not created".to_string())?;
+
+            if json {
+                print_json(&todo)?;
+            } else {
+                println!("Added {}  {}", short_id(&todo.id), todo.title);
+            }
+        }
+        Command::Ls { list, all } => {
+            let data = store.read()?;
+            let list_id = list
+                .as_deref()
+                .map(|list| resolve_list_id(&data, list))
+                .transpose()?;
+            let availability = classify_availability(&data.todos, now_millis());
+
+            let todos = ordered_todos(&data)
+                .into_iter()
+                .filter(|(todo, _)| {
+                    list_id
+                        .as_deref()
+                        .map_or(true, |list_id| todo.list_id.as_deref() == Some(list_id))
+                })
+                .filter(|(todo, _)| {
+                    all || availability.get(&todo.id) == Some(&TodoAvailability::Available)
+                })
+                .collect::<Vec<_>>();
+
+            if json {
+                print_json(&todos.iter().map(|(todo, _)| *todo).collect::<Vec<_>>())?;
+            } else {
+                print_todos(&data, &todos, &availability);
+            }
+        }
+        Command::Done { id } => {
+            let (before, after) = store.update(|data| {
+                Ok(Change::Complete {
+                    id: resolve_todo_id(data, &id)?,
+                })
+            })?;
+            let ids = changed_ids(&before, &after, |previous, todo| {
+                previous.is_some_and(|previous| previous.completed_at != todo.completed_at)
+            });
+            print_affected(json, "Completed", ids)?;
+        }
+        Command::Rm { id } => {
+            let (before, after) = store.update(|data| {
+                Ok(Change::Delete {
+                    id: resolve_todo_id(data, &id)?,
+                })
+            })?;
+            // Deleted todos are the ones missing afterwards.
+            let ids = changed_ids(&after, &before, |remaining, _| remaining.is_none());
+            print_affected(json, "Deleted", ids)?;
+        }
+        Command::Mv { id, list } => {
+            let (before, after) = store.update(|data| {
+                Ok(Change::Move {
+                    id: resolve_todo_id(data, &id)?,
+                    list_id: resolve_list_id(data, &list)?,
+                })
+            })?;
+            let ids = changed_ids(&before, &after, |previous, todo| {
+                previous.is_some_and(|previous| previous.list_id != todo.list_id)
+            });
+            print_affected(json, "Moved", ids)?;
+        }
+        Command::Search { query, all } => {
+            let data = store.read()?;
+            let needle = query.to_lowercase();
+            let todos = ordered_todos(&data)
+                .into_iter()
+                .filter(|(todo, _)| all || todo.completed_at.is_none())
+                .filter(|(todo, _)| {
+                    todo.title.to_lowercase().contains(&needle)
+                        || todo
+                            .details
+                            .as_deref()
+                            .is_some_and(|details| details.to_lowercase().contains(&needle))
+                })
+                .map(|(todo, _)| (todo, 0))
+                .collect::<Vec<_>>();
+
+            if json {
+                print_json(&todos.iter().map(|(todo, _)| *todo).collect::<Vec<_>>())?;
+            } else {
+                let availability = classify_availability(&data.todos, now_millis());
+                print_todos(&data, &todos, &availability);
+            }
+        }
+        Command::Lists => {
+            let data = store.read()?;
+            let summaries = list_summaries(&data);
+
+            if json {
+                print_json(&summaries)?;
+            } else {
+                let rows = summaries
+                    .iter()
+                    .map(|list| {
+                        vec![
+                            if list.active { "*" } else { "" }.to_string(),
+                            list.id.clone(),
+                            list.name.clone(),
+                            list.open.to_string(),
+                            list.completed.to_string(),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                print_table(&["", "ID", "NAME", "OPEN", "DONE"], &rows);
+            }
+        }
+        Command::Help => {}
+    }
+
+    Ok(())
+}
+
+pub(crate) fn find_todo<'a>(data: &'a AppData, id: &str) -> &'a Todo {
+    data.todos
+        .iter()
+        .find(|todo| todo.id == id)
+        .expect("id comes from the same data")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ListSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub open: usize,
+    pub completed: usize,
+    pub active: bool,
+}
+
+pub(crate) fn list_summaries(data: &AppData) -> Vec<ListSummary> {
+    data.settings
+        .lists
+        .iter()
+        .map(|list| {
+            let in_list = data
+                .todos
+                .iter()
+                .filter(|todo| todo.list_id.as_deref() == Some(list.id.as_str()));
+            let (completed, open): (Vec<_>, Vec<_>) =
+                in_list.partition(|todo| todo.completed_at.is_some());
+
+            ListSummary {
+                id: list.id.clone(),
+                name: list.name.clone(),
+                icon: list.icon.clone(),
+                open: open.len(),
+                completed: completed.len(),
+                active: list.id == data.settings.active_list_id,
+            }
+        })
+        .collect()
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..SHORT_ID_LENGTH).unwrap_or(id)
+}
+
+fn format_millis(millis: i64) -> String {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|date_time| date_time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("failed to serialize output: {error}"))?;
+    println!("{payload}");
+    Ok(())
+}
+
+fn print_affected(json: bool, verb: &str, mut ids: Vec<String>) -> Result<(), String> {
+    ids.sort();
+
+    if json {
+        return print_json(&serde_json::json!({ "ids": ids }));
+    }
+
+    println!("{verb} {} todo(s)", ids.len());
+    Ok(())
+}
+
+fn print_todos(
+    data: &AppData,
+    todos: &[(&Todo, usize)],
+    availability: &HashMap<String, TodoAvailability>,
+) {
+    if todos.is_empty() {
+        println!("No todos");
+        return;
+    }
+
+    let list_names = data
+        .settings
+        .lists
+        .iter()
+        .map(|list| (list.id.as_str(), list.name.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let rows = todos
+        .iter()
+        .map(|(todo, depth)| {
+            let status = match availability.get(&todo.id) {
+                Some(TodoAvailability::Completed) => "[x]",
+                Some(TodoAvailability::Deferred) => "[~]",
+                _ => "[ ]",
+            };
+            let star = if todo.starred { " *" } else { "" };
+
+            vec![
+                short_id(&todo.id).to_string(),
+                status.to_string(),
+                format!("{}{}{star}", "  ".repeat(*depth), todo.title),
+                todo.list_id
+                    .as_deref()
+                    .and_then(|list_id| list_names.get(list_id))
+                    .map(|name| name.to_string())
+                    .unwrap_or_default(),
+                todo.reminder_at.map(format_millis).unwrap_or_default(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(&["ID", "", "TITLE", "LIST", "REMINDER"], &rows);
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = width.saturating_sub(cell.chars().count());
+                format!("{cell}{}", " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", render(headers.to_vec()));
+    for row in rows {
+        println!("{}", render(row.iter().map(String::as_str).collect()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_commands_and_options() {
+        let invocation = parse(args(&[
+            "add",
+            "Buy",
+            "milk",
+            "--list=Home",
+            "--details",
+            "2 l",
+            "--json",
+        ]))
+        .expect("valid");
+        assert!(invocation.json);
+        assert_eq!(
+            invocation.command,
+            Command::Add {
+                title: "Buy milk".to_string(),
+                list: Some("Home".to_string()),
+                details: Some("2 l".to_string()),
+                parent: None,
+                remind: None,
+            }
+        );
+
+        let invocation = parse(args(&["ls", "--all"])).expect("valid");
+        assert_eq!(
+            invocation.command,
+            Command::Ls {
+                list: None,
+                all: true
+            }
+        );
+        assert!(is_cli_invocation(&args(&["mv", "abc", "Work"])));
+        assert!(!is_cli_invocation(&args(&["--overlay"])));
+
+        for invalid in [
+            &["add"][..],
+            &["done", "a", "b"],
+            &["mv", "a"],
+            &["ls", "--list"],
+            &["ls", "--details", "x"],
+            &["ls", "--verbose"],
+            &["add", "x", "--remind", "tomorrow"],
+            &["add", "x", "--remind", "2026"],
+            &["sync"],
+        ] {
+            assert!(
+                matches!(parse(args(invalid)), Err(CliError::Usage(_))),
+                "{invalid:?}"
+            );
+        }
+    }
+}
@@ -2,21 +2,45 @@ use std::collections::{HashMap, HashSet};
 use tauri::{AppHandle, Emitter, Manager, State};
 use uuid::Uuid;
 
+use crate::http_api;
+use crate::i18n;
 use crate::shortcuts;
 use crate::storage::{
-    normalize_shortcut, now_millis, persist, AppData, AppState, Settings, Todo, TodoLabel,
-    TodoList, TodoPriority, DEFAULT_LIST_ID,
+    broadcast_state, classify_availability, normalize_daily_summary_time, normalize_http_api,
+    normalize_script_hooks, normalize_shortcut, normalize_webhooks, now_millis, persist, AppData,
+    AppState, Settings, Todo, TodoAvailability, TodoLabel, TodoList, TodoPriority, DEFAULT_LIST_ID,
 };
+use crate::tray;
 use crate::window;
 
-fn lock_error(name: &str) -> String {
+pub(crate) fn lock_error(name: &str) -> String {
     format!("failed to lock {name} state")
 }
 
-fn persist_state(app: &AppHandle, state: &State<'_, AppState>) -> Result<AppData, String> {
-    let snapshot = state.data.lock().map_err(|_| lock_error("todo"))?.clone();
-    persist(app, &snapshot)?;
-    Ok(snapshot)
+pub(crate) fn persist_state(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<AppData, String> {
+    let (snapshot, revision) = {
+        let guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        (guard.clone(), state.next_revision())
+    };
+
+    // Saves are serialized and follow revision order: a snapshot older than
+    // the last one saved is already included in it.
+    let mut persisted_revision = state
+        .persisted_revision
+        .lock()
+        .map_err(|_| lock_error("storage"))?;
+    if *persisted_revision <= revision {
+        persist(app, &snapshot)?;
+        *persisted_revision = revision;
+        broadcast_state(app, revision, &snapshot);
+    }
+    Ok(AppData {
+        revision: Some(revision),
+        ..snapshot
+    })
 }
 
 fn normalize_optional_text(value: Option<String>) -> Option<String> {
@@ -58,12 +82,23 @@ fn normalize_label_color(value: &str) -> String {
     }
 }
 
+fn default_label(language: &str) -> TodoLabel {
+    TodoLabel {
+        id: "general".to_string(),
+        name: i18n::messages(language).default_label_name.to_string(),
+        color: "slate".to_string(),
+    }
+}
+
 fn sanitize_settings(mut settings: Settings) -> Settings {
+    let messages = i18n::messages(&settings.language);
+
     if settings.lists.is_empty() {
         settings.lists.push(TodoList {
             id: DEFAULT_LIST_ID.to_string(),
-            name: "Mes tâches".to_string(),
+            name: messages.default_list_name.to_string(),
             icon: None,
+            notification_sound: None,
             created_at: now_millis(),
         });
     }
@@ -72,9 +107,9 @@ fn sanitize_settings(mut settings: Settings) -> Settings {
         list.name = normalize_list_name(
             &list.name,
             if index == 0 {
-                "Mes tâches"
+                messages.default_list_name
             } else {
-                "Nouvelle liste"
+                messages.new_list_name
             },
         );
     }
@@ -92,11 +127,7 @@ fn sanitize_settings(mut settings: Settings) -> Settings {
     }
 
     if settings.labels.is_empty() {
-        settings.labels.push(TodoLabel {
-            id: "general".to_string(),
-            name: "Général".to_string(),
-            color: "slate".to_string(),
-        });
+        settings.labels.push(default_label(&settings.language));
     } else {
         let mut used_label_ids = HashSet::new();
         for (index, label) in settings.labels.iter_mut().enumerate() {
@@ -114,6 +145,10 @@ fn sanitize_settings(mut settings: Settings) -> Settings {
     }
 
     settings.global_shortcut = normalize_shortcut(&settings.global_shortcut);
+    settings.daily_summary.time = normalize_daily_summary_time(&settings.daily_summary.time);
+    settings.http_api = normalize_http_api(settings.http_api);
+    settings.webhooks = normalize_webhooks(settings.webhooks);
+    settings.script_hooks = normalize_script_hooks(settings.script_hooks);
 
     settings.legacy_list_name = None;
     settings
@@ -148,37 +183,31 @@ fn collect_subtree_ids(todos: &[Todo], root_id: &str) -> HashSet<String> {
     visited
 }
 
-fn push_todo(
-    state: &State<'_, AppState>,
+/// Adds a todo to `data` with the same validation as the `create_todo`
+/// command and returns its id, or `None` when the title is blank.
+pub(crate) fn insert_todo(
+    data: &mut AppData,
     title: String,
     details: Option<String>,
     reminder_at: Option<i64>,
     parent_id: Option<String>,
     list_id: Option<String>,
-) -> Result<(), String> {
+) -> Option<String> {
     let trimmed_title = title.trim();
     if trimmed_title.is_empty() {
-        return Ok(());
+        return None;
     }
 
     let normalized_details = normalize_optional_text(details);
     let normalized_parent_id = normalize_optional_id(parent_id);
     let normalized_list_id = normalize_optional_id(list_id);
 
-    let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
     let target_list_id = normalized_list_id
-        .filter(|candidate| {
-            guard
-                .settings
-                .lists
-                .iter()
-                .any(|list| list.id == *candidate)
-        })
-        .unwrap_or_else(|| guard.settings.active_list_id.clone());
+        .filter(|candidate| data.settings.lists.iter().any(|list| list.id == *candidate))
+        .unwrap_or_else(|| data.settings.active_list_id.clone());
 
     let validated_parent_id = normalized_parent_id.and_then(|candidate_parent| {
-        guard
-            .todos
+        data.todos
             .iter()
             .find(|todo| {
                 todo.id == candidate_parent
@@ -187,7 +216,7 @@ fn push_todo(
             .map(|todo| todo.id.clone())
     });
 
-    let next_sort_index = guard
+    let next_sort_index = data
         .todos
         .iter()
         .filter(|todo| {
@@ -199,8 +228,9 @@ fn push_todo(
         .max()
         .map(|value| value.saturating_add(1));
 
-    guard.todos.push(Todo {
-        id: Uuid::new_v4().to_string(),
+    let id = Uuid::new_v4().to_string();
+    data.todos.push(Todo {
+        id: id.clone(),
         title: trimmed_title.to_string(),
         details: normalized_details,
         parent_id: validated_parent_id,
@@ -212,9 +242,252 @@ fn push_todo(
         created_at: now_millis(),
         completed_at: None,
         reminder_at,
+        start_at: None,
+        updated_at: None,
     });
 
-    Ok(())
+    Some(id)
+}
+
+fn push_todo(
+    state: &State<'_, AppState>,
+    title: String,
+    details: Option<String>,
+    reminder_at: Option<i64>,
+    parent_id: Option<String>,
+    list_id: Option<String>,
+) -> Result<Option<String>, String> {
+    let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+    Ok(insert_todo(
+        &mut guard,
+        title,
+        details,
+        reminder_at,
+        parent_id,
+        list_id,
+    ))
+}
+
+/// Marks `id` and its subtasks as completed (or open again) and returns the
+/// ids that were touched.
+pub(crate) fn apply_todo_completed(
+    data: &mut AppData,
+    id: &str,
+    completed: bool,
+) -> HashSet<String> {
+    let now = now_millis();
+    let next_completed_at = completed.then_some(now);
+    let ids = collect_subtree_ids(&data.todos, id);
+
+    for todo in data.todos.iter_mut() {
+        if ids.contains(&todo.id) {
+            todo.completed_at = next_completed_at;
+            todo.updated_at = Some(now);
+        }
+    }
+
+    ids
+}
+
+/// Removes `id` and its subtasks and returns the removed ids.
+pub(crate) fn remove_todo_tree(data: &mut AppData, id: &str) -> HashSet<String> {
+    let mut ids = collect_subtree_ids(&data.todos, id);
+    if ids.is_empty() {
+        ids.insert(id.to_string());
+    }
+    data.todos.retain(|todo| !ids.contains(&todo.id));
+    ids
+}
+
+/// Moves `id` and its subtasks to `list_id`, appending the root at the end of
+/// the target list. Returns `false` when the list or the todo is unknown.
+pub(crate) fn move_todo_tree(data: &mut AppData, id: &str, list_id: &str) -> bool {
+    let normalized_list_id = normalize_list_name(list_id, "");
+    if normalized_list_id.is_empty() {
+        return false;
+    }
+
+    if !data
+        .settings
+        .lists
+        .iter()
+        .any(|list| list.id == normalized_list_id)
+    {
+        return false;
+    }
+
+    let Some(root_completed) = data
+        .todos
+        .iter()
+        .find(|todo| todo.id == id)
+        .map(|todo| todo.completed_at.is_some())
+    else {
+        return false;
+    };
+
+    let moved_ids = collect_subtree_ids(&data.todos, id);
+
+    let next_root_sort_index = data
+        .todos
+        .iter()
+        .filter(|todo| {
+            todo.list_id.as_deref() == Some(normalized_list_id.as_str())
+                && todo.parent_id.is_none()
+                && (todo.completed_at.is_some() == root_completed)
+        })
+        .filter_map(|todo| todo.sort_index)
+        .max()
+        .map(|value| value.saturating_add(1));
+
+    let now = now_millis();
+    for todo in &mut data.todos {
+        if !moved_ids.contains(&todo.id) {
+            continue;
+        }
+
+        todo.list_id = Some(normalized_list_id.clone());
+        todo.updated_at = Some(now);
+        if todo.id == id {
+            todo.parent_id = None;
+            todo.sort_index = next_root_sort_index;
+            continue;
+        }
+
+        if todo
+            .parent_id
+            .as_ref()
+            .map(|parent_id| !moved_ids.contains(parent_id))
+            .unwrap_or(false)
+        {
+            todo.parent_id = None;
+        }
+    }
+
+    true
+}
+
+/// Adds a list with the same validation as the `create_list` command, makes
+/// it the active list and returns its id.
+pub(crate) fn insert_list(data: &mut AppData, name: &str) -> String {
+    let list_id = Uuid::new_v4().to_string();
+    let list_name =
+        normalize_list_name(name, i18n::messages(&data.settings.language).new_list_name);
+    data.settings.lists.push(TodoList {
+        id: list_id.clone(),
+        name: list_name,
+        icon: None,
+        notification_sound: None,
+        created_at: now_millis(),
+    });
+    data.settings.active_list_id = list_id.clone();
+    list_id
+}
+
+/// Applies the given changes to list `id` as `rename_list`, `set_list_icon`
+/// and `set_list_notification_sound` do. Returns `false` when the list is
+/// unknown.
+pub(crate) fn edit_list(
+    data: &mut AppData,
+    id: &str,
+    name: Option<&str>,
+    icon: Option<Option<String>>,
+    sound: Option<Option<String>>,
+) -> bool {
+    let Some(list) = data.settings.lists.iter_mut().find(|list| list.id == id) else {
+        return false;
+    };
+
+    if let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) {
+        list.name = name.to_string();
+    }
+    if let Some(icon) = icon {
+        list.icon = icon;
+    }
+    if let Some(sound) = sound {
+        list.notification_sound = normalize_optional_text(sound);
+    }
+    true
+}
+
+/// Removes list `id` as saving the settings without it does: its todos move
+/// to the active list, which becomes the first list when it was `id`.
+/// Returns `false` when the list is unknown or the last one.
+pub(crate) fn remove_list(data: &mut AppData, id: &str) -> bool {
+    if data.settings.lists.len() < 2 || !data.settings.lists.iter().any(|list| list.id == id) {
+        return false;
+    }
+
+    data.settings.lists.retain(|list| list.id != id);
+    if data.settings.active_list_id == id {
+        data.settings.active_list_id = data.settings.lists[0].id.clone();
+    }
+
+    let now = now_millis();
+    for todo in &mut data.todos {
+        if todo.list_id.as_deref() == Some(id) {
+            todo.list_id = Some(data.settings.active_list_id.clone());
+            todo.updated_at = Some(now);
+        }
+    }
+    true
+}
+
+/// Adds a label with the same normalization as `update_settings` and returns
+/// its id.
+pub(crate) fn insert_label(data: &mut AppData, name: &str, color: &str) -> String {
+    let id = Uuid::new_v4().to_string();
+    let fallback_name = format!("Label {}", data.settings.labels.len() + 1);
+    data.settings.labels.push(TodoLabel {
+        id: id.clone(),
+        name: normalize_list_name(name, &fallback_name),
+        color: normalize_label_color(color),
+    });
+    id
+}
+
+/// Renames or recolors label `id`, ignoring a blank name. Returns `false`
+/// when the label is unknown.
+pub(crate) fn edit_label(
+    data: &mut AppData,
+    id: &str,
+    name: Option<&str>,
+    color: Option<&str>,
+) -> bool {
+    let Some(label) = data.settings.labels.iter_mut().find(|label| label.id == id) else {
+        return false;
+    };
+
+    if let Some(name) = name {
+        label.name = normalize_list_name(name, &label.name);
+    }
+    if let Some(color) = color {
+        label.color = normalize_label_color(color);
+    }
+    true
+}
+
+/// Removes label `id` from the settings and from its todos. As in
+/// `update_settings`, the default label comes back when it was the last one.
+/// Returns `false` when the label is unknown.
+pub(crate) fn remove_label(data: &mut AppData, id: &str) -> bool {
+    if !data.settings.labels.iter().any(|label| label.id == id) {
+        return false;
+    }
+
+    data.settings.labels.retain(|label| label.id != id);
+    if data.settings.labels.is_empty() {
+        let label = default_label(&data.settings.language);
+        data.settings.labels.push(label);
+    }
+
+    let now = now_millis();
+    for todo in &mut data.todos {
+        if todo.label_id.as_deref() == Some(id) {
+            todo.label_id = None;
+            todo.updated_at = Some(now);
+        }
+    }
+    true
 }
 
 #[tauri::command]
@@ -377,18 +650,16 @@ pub fn reset_all_data(app: AppHandle, state: State<'_, AppState>) -> Result<(),
         // Don't fail the reset if shortcut registration fails
     }
 
-    // Delete the data file
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|error| format!("failed to resolve appDataDir: {error}"))?;
+    // Language falls back to `auto`, so tray labels may change
+    if let Err(error) = tray::refresh_menu(&app) {
+        log::error!("failed to rebuild tray menu after reset: {error}");
+    }
 
-    let path = app_dir.join(crate::storage::STORAGE_FILE_NAME);
+    // The HTTP API is opt-in, so it stops with the default settings
+    http_api::apply_settings(&app, &Default::default());
 
-    if path.exists() {
-        std::fs::remove_file(&path)
-            .map_err(|error| format!("failed to delete data file: {error}"))?;
-    }
+    // Save the defaults, which also notifies every subscriber
+    persist_state(&app, &state)?;
 
     // Emit event to notify frontend that data has been reset
     app.emit("data-reset", ()).ok();
@@ -399,7 +670,31 @@ pub fn reset_all_data(app: AppHandle, state: State<'_, AppState>) -> Result<(),
 
 #[tauri::command]
 pub fn load_state(state: State<'_, AppState>) -> AppData {
-    state.snapshot()
+    let guard = state.data.lock().expect("todo state lock poisoned");
+    AppData {
+        revision: Some(state.revision()),
+        ..guard.clone()
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSince {
+    pub revision: u64,
+    /// `None` when nothing changed after the given revision.
+    pub data: Option<AppData>,
+}
+
+/// Lets a webview that may have missed `state-changed` events catch up.
+#[tauri::command]
+pub fn load_state_since(revision: u64, state: State<'_, AppState>) -> Result<StateSince, String> {
+    let guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+    let current = state.revision();
+
+    Ok(StateSince {
+        revision: current,
+        data: (current != revision).then(|| guard.clone()),
+    })
 }
 
 #[tauri::command]
@@ -472,6 +767,7 @@ pub fn update_todo(
             todo.title = trimmed_title.to_string();
             todo.details = normalized_details;
             todo.reminder_at = payload.reminder_at;
+            todo.updated_at = Some(now_millis());
         }
     }
 
@@ -508,18 +804,9 @@ pub fn set_todo_completed(
         completed
     );
 
-    let next_completed_at = completed.then_some(now_millis());
-
     let affected_ids = {
         let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
-        let ids = collect_subtree_ids(&guard.todos, &id);
-
-        for todo in guard.todos.iter_mut() {
-            if ids.contains(&todo.id) {
-                todo.completed_at = next_completed_at;
-            }
-        }
-        ids
+        apply_todo_completed(&mut guard, &id, completed)
     };
 
     if completed && !affected_ids.is_empty() {
@@ -547,6 +834,7 @@ pub fn set_todo_starred(
 
         if let Some(todo) = guard.todos.iter_mut().find(|todo| todo.id == id) {
             todo.starred = starred;
+            todo.updated_at = Some(now_millis());
         }
     }
 
@@ -565,6 +853,7 @@ pub fn set_todo_priority(
 
         if let Some(todo) = guard.todos.iter_mut().find(|todo| todo.id == id) {
             todo.priority = priority;
+            todo.updated_at = Some(now_millis());
         }
     }
 
@@ -593,6 +882,7 @@ pub fn set_todo_label(
 
         if let Some(todo) = guard.todos.iter_mut().find(|todo| todo.id == id) {
             todo.label_id = valid_label_id;
+            todo.updated_at = Some(now_millis());
         }
     }
 
@@ -605,18 +895,9 @@ pub fn create_list(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<AppData, String> {
-    let list_name = normalize_list_name(&name, "Nouvelle liste");
-    let list_id = Uuid::new_v4().to_string();
-
     {
         let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
-        guard.settings.lists.push(TodoList {
-            id: list_id.clone(),
-            name: list_name,
-            icon: None,
-            created_at: now_millis(),
-        });
-        guard.settings.active_list_id = list_id;
+        insert_list(&mut guard, &name);
     }
 
     persist_state(&app, &state)
@@ -661,6 +942,25 @@ pub fn set_list_icon(
     persist_state(&app, &state)
 }
 
+#[tauri::command]
+pub fn set_list_notification_sound(
+    id: String,
+    sound: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let normalized_sound = normalize_optional_text(sound);
+
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        if let Some(list) = guard.settings.lists.iter_mut().find(|list| list.id == id) {
+            list.notification_sound = normalized_sound;
+        }
+    }
+
+    persist_state(&app, &state)
+}
+
 #[tauri::command]
 pub fn set_active_list(
     id: String,
@@ -684,68 +984,9 @@ pub fn move_todo_to_list(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<AppData, String> {
-    let normalized_list_id = normalize_list_name(&list_id, "");
-    if normalized_list_id.is_empty() {
-        return persist_state(&app, &state);
-    }
-
     {
         let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
-
-        if !guard
-            .settings
-            .lists
-            .iter()
-            .any(|list| list.id == normalized_list_id)
-        {
-            return persist_state(&app, &state);
-        }
-
-        let moved_ids = collect_subtree_ids(&guard.todos, &id);
-        if moved_ids.is_empty() {
-            return persist_state(&app, &state);
-        }
-
-        let root_completed = guard
-            .todos
-            .iter()
-            .find(|todo| todo.id == id)
-            .map(|todo| todo.completed_at.is_some())
-            .unwrap_or(false);
-
-        let next_root_sort_index = guard
-            .todos
-            .iter()
-            .filter(|todo| {
-                todo.list_id.as_deref() == Some(normalized_list_id.as_str())
-                    && todo.parent_id.is_none()
-                    && (todo.completed_at.is_some() == root_completed)
-            })
-            .filter_map(|todo| todo.sort_index)
-            .max()
-            .map(|value| value.saturating_add(1));
-
-        for todo in &mut guard.todos {
-            if !moved_ids.contains(&todo.id) {
-                continue;
-            }
-
-            todo.list_id = Some(normalized_list_id.clone());
-            if todo.id == id {
-                todo.parent_id = None;
-                todo.sort_index = next_root_sort_index;
-                continue;
-            }
-
-            if todo
-                .parent_id
-                .as_ref()
-                .map(|parent_id| !moved_ids.contains(parent_id))
-                .unwrap_or(false)
-            {
-                todo.parent_id = None;
-            }
-        }
+        move_todo_tree(&mut guard, &id, &list_id);
     }
 
     persist_state(&app, &state)
@@ -841,9 +1082,11 @@ pub fn reorder_todos(
                     .map(|(index, id)| (id, index as i64))
                     .collect();
 
+                let now = now_millis();
                 for todo in &mut guard.todos {
                     if let Some(rank) = rank_by_id.get(&todo.id) {
                         todo.sort_index = Some(*rank);
+                        todo.updated_at = Some(now);
                     }
                 }
             }
@@ -863,12 +1106,7 @@ pub fn delete_todo(
 
     let deleted_ids = {
         let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
-        let mut ids = collect_subtree_ids(&guard.todos, &id);
-        if ids.is_empty() {
-            ids.insert(id.clone());
-        }
-        guard.todos.retain(|todo| !ids.contains(&todo.id));
-        ids
+        remove_todo_tree(&mut guard, &id)
     };
 
     {
@@ -920,15 +1158,15 @@ pub fn update_settings(
     state: State<'_, AppState>,
 ) -> Result<AppData, String> {
     let sanitized_settings = sanitize_settings(settings);
-    let previous_shortcut = {
-        state
-            .data
-            .lock()
-            .map_err(|_| lock_error("todo"))?
-            .settings
-            .global_shortcut
-            .clone()
+    let (previous_shortcut, previous_language) = {
+        let guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        (
+            guard.settings.global_shortcut.clone(),
+            guard.settings.language.clone(),
+        )
     };
+    let language_changed = sanitized_settings.language != previous_language;
+    let http_api = sanitized_settings.http_api.clone();
 
     if sanitized_settings.global_shortcut != previous_shortcut {
         shortcuts::replace_registered_shortcut(&app, &sanitized_settings.global_shortcut)
@@ -937,7 +1175,10 @@ pub fn update_settings(
 
     {
         let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        // Owned by the scheduler: the frontend may hold an older value.
+        let last_sent_at = guard.settings.daily_summary.last_sent_at;
         guard.settings = sanitized_settings;
+        guard.settings.daily_summary.last_sent_at = last_sent_at;
 
         let valid_list_ids: HashSet<String> = guard
             .settings
@@ -957,6 +1198,7 @@ pub fn update_settings(
                 Some(list_id) if valid_list_ids.contains(list_id) => {}
                 _ => {
                     todo.list_id = Some(fallback_list_id.clone());
+                    todo.updated_at = Some(now_millis());
                 }
             }
 
@@ -964,12 +1206,21 @@ pub fn update_settings(
                 Some(label_id) if valid_label_ids.contains(label_id) => {}
                 _ => {
                     todo.label_id = None;
+                    todo.updated_at = Some(now_millis());
                 }
             }
         }
     }
 
-    persist_state(&app, &state)
+    if language_changed {
+        if let Err(error) = tray::refresh_menu(&app) {
+            log::error!("failed to rebuild tray menu after language change: {error}");
+        }
+    }
+
+    let data = persist_state(&app, &state)?;
+    http_api::apply_settings(&app, &http_api);
+    Ok(data)
 }
 
 #[tauri::command]
@@ -1031,6 +1282,7 @@ pub fn set_todo_reminder(
         if let Some(todo) = guard.todos.iter_mut().find(|todo| todo.id == id) {
             should_reset_reminder_notification = todo.reminder_at != reminder_at;
             todo.reminder_at = reminder_at;
+            todo.updated_at = Some(now_millis());
         }
     }
 
@@ -1045,6 +1297,73 @@ pub fn set_todo_reminder(
     persist_state(&app, &state)
 }
 
+#[tauri::command]
+pub fn set_todo_start(
+    id: String,
+    start_at: Option<i64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let mut should_reset_available_notification = false;
+
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+
+        if let Some(todo) = guard.todos.iter_mut().find(|todo| todo.id == id) {
+            should_reset_available_notification = todo.start_at != start_at;
+            todo.start_at = start_at;
+            todo.updated_at = Some(now_millis());
+        }
+    }
+
+    if should_reset_available_notification {
+        let mut notified_guard = state
+            .available_notified
+            .lock()
+            .map_err(|_| lock_error("reminder"))?;
+        notified_guard.remove(&id);
+    }
+
+    persist_state(&app, &state)
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodosByAvailability {
+    pub available: Vec<Todo>,
+    pub deferred: Vec<Todo>,
+    pub completed: Vec<Todo>,
+}
+
+#[tauri::command]
+pub fn query_todos(
+    list_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TodosByAvailability, String> {
+    let normalized_list_id = normalize_optional_id(list_id);
+    let guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+    let availability = classify_availability(&guard.todos, now_millis());
+
+    let mut result = TodosByAvailability::default();
+    for todo in &guard.todos {
+        if normalized_list_id
+            .as_deref()
+            .is_some_and(|list_id| todo.list_id.as_deref() != Some(list_id))
+        {
+            continue;
+        }
+
+        let bucket = match availability.get(&todo.id) {
+            Some(TodoAvailability::Deferred) => &mut result.deferred,
+            Some(TodoAvailability::Completed) => &mut result.completed,
+            _ => &mut result.available,
+        };
+        bucket.push(todo.clone());
+    }
+
+    Ok(result)
+}
+
 #[tauri::command]
 pub fn hide_overlay(app: AppHandle) -> Result<(), String> {
     window::hide_main_window(&app).map_err(|error| error.to_string())
@@ -0,0 +1,149 @@
+//! `org.blinkdo` service on the session bus for desktop shell extensions and
+//! scripts, e.g.
+//!
+//! ```sh
+//! busctl --user call org.blinkdo /org/blinkdo org.blinkdo AddTodo s "Buy milk"
+//! ```
+//!
+//! Methods go through the same command functions as the frontend. The
+//! `TodosChanged` signal carries the number of open todos after every save.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+use tauri::{AppHandle, Manager};
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::fdo;
+use zbus::object_server::SignalEmitter;
+
+use crate::commands;
+use crate::storage::{AppData, AppState};
+use crate::window;
+
+const BUS_NAME: &str = "org.blinkdo";
+const OBJECT_PATH: &str = "/org/blinkdo";
+
+/// Keeps the bus connection alive and queues `TodosChanged` signals.
+#[derive(Default)]
+pub struct DbusService {
+    signals: Mutex<Option<Sender<u32>>>,
+}
+
+struct BlinkDo {
+    app: AppHandle,
+}
+
+#[zbus::interface(name = "org.blinkdo")]
+impl BlinkDo {
+    fn toggle_overlay(&self) -> fdo::Result<()> {
+        window::toggle_overlay(&self.app).map_err(|error| fdo::Error::Failed(error.to_string()))
+    }
+
+    /// Adds a todo to the active list and returns its id.
+    fn add_todo(&self, title: String) -> fdo::Result<String> {
+        let state = self.app.state::<AppState>();
+        let before = state
+            .snapshot()
+            .todos
+            .into_iter()
+            .map(|todo| todo.id)
+            .collect::<HashSet<_>>();
+
+        let data = commands::create_todo(title, None, None, None, None, self.app.clone(), state)
+            .map_err(fdo::Error::Failed)?;
+
+        data.todos
+            .into_iter()
+            .find(|todo| !before.contains(&todo.id))
+            .map(|todo| todo.id)
+            .ok_or_else(|| fdo::Error::InvalidArgs("title must not be empty".to_string()))
+    }
+
+    /// Returns `(id, title, list id)` for every open todo.
+    fn list_open_todos(&self) -> Vec<(String, String, String)> {
+        self.app
+            .state::<AppState>()
+            .snapshot()
+            .todos
+            .into_iter()
+            .filter(|todo| todo.completed_at.is_none())
+            .map(|todo| (todo.id, todo.title, todo.list_id.unwrap_or_default()))
+            .collect()
+    }
+
+    /// Completes a todo and its subtasks. Returns false for unknown ids.
+    fn complete_todo(&self, id: String) -> fdo::Result<bool> {
+        let state = self.app.state::<AppState>();
+        if !state.snapshot().todos.iter().any(|todo| todo.id == id) {
+            return Ok(false);
+        }
+
+        commands::set_todo_completed(id, true, self.app.clone(), state)
+            .map_err(fdo::Error::Failed)?;
+        Ok(true)
+    }
+
+    #[zbus(signal)]
+    async fn todos_changed(emitter: &SignalEmitter<'_>, open_count: u32) -> zbus::Result<()>;
+}
+
+/// Claims `org.blinkdo` on the session bus. Failing to do so (no session
+/// bus, name already owned) only disables the service.
+pub fn start_service(app: &AppHandle) -> zbus::Result<()> {
+    let connection = Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, BlinkDo { app: app.clone() })?
+        .build()?;
+
+    // Signals are sent from their own thread because saves also happen
+    // inside method calls, which run on the connection's executor.
+    let (sender, receiver) = mpsc::channel::<u32>();
+    thread::spawn(move || {
+        for open_count in receiver {
+            emit_todos_changed(&connection, open_count);
+        }
+    });
+
+    if let Ok(mut signals) = app.state::<DbusService>().signals.lock() {
+        *signals = Some(sender);
+    }
+
+    log::info!("D-Bus service {BUS_NAME} ready");
+    Ok(())
+}
+
+/// Queues a `TodosChanged` signal for the saved `data`.
+pub fn publish_change(app: &AppHandle, data: &AppData) {
+    let Some(service) = app.try_state::<DbusService>() else {
+        return;
+    };
+    let Ok(signals) = service.signals.lock() else {
+        return;
+    };
+
+    if let Some(sender) = signals.as_ref() {
+        let open_count = data
+            .todos
+            .iter()
+            .filter(|todo| todo.completed_at.is_none())
+            .count();
+        sender.send(open_count as u32).ok();
+    }
+}
+
+fn emit_todos_changed(connection: &Connection, open_count: u32) {
+    let result = connection.emit_signal(
+        None::<&str>,
+        OBJECT_PATH,
+        BUS_NAME,
+        "TodosChanged",
+        &(open_count,),
+    );
+
+    if let Err(error) = result {
+        log::warn!("failed to emit TodosChanged: {error}");
+    }
+}
@@ -0,0 +1,131 @@
+//! `blinkdo://` links from bookmarklets, emails and other apps:
+//!
+//! - `blinkdo://add?title=...&details=...&list=...&remind=...&parent=...`
+//! - `blinkdo://open?todo=<id>`
+//!
+//! Links go through the same command functions as the frontend, so the
+//! validation (blank titles, unknown lists or parents) is identical.
+
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::commands;
+use crate::storage::{parse_date_time, resolve_list_id, AppState};
+use crate::window;
+
+pub const SCHEME: &str = "blinkdo";
+
+/// Registers the scheme where it is done at runtime and handles the link the
+/// app was launched with, if any.
+pub fn init(app: &AppHandle) {
+    // Installed bundles register the scheme themselves; AppImages and
+    // development builds do not.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(error) = app.deep_link().register_all() {
+        log::warn!("failed to register {SCHEME}:// links: {error}");
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, &url);
+        }
+    });
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                handle_url(app, &url);
+            }
+        }
+        Ok(None) => {}
+        Err(error) => log::warn!("failed to read launch link: {error}"),
+    }
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.starts_with(&format!("{SCHEME}:"))
+}
+
+fn handle_url(app: &AppHandle, url: &Url) {
+    if let Err(error) = apply_url(app, url) {
+        log::warn!("ignored link {url}: {error}");
+    }
+}
+
+fn apply_url(app: &AppHandle, url: &Url) -> Result<(), String> {
+    if url.scheme() != SCHEME {
+        return Err("unsupported scheme".to_string());
+    }
+
+    // `blinkdo://add?...` has the action as host, `blinkdo:add?...` as path.
+    let action = url
+        .host_str()
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/')
+        .to_ascii_lowercase();
+    let query = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+
+    match action.as_str() {
+        "add" => add_todo(app, &query),
+        "open" => open_todo(app, &query),
+        other => Err(format!("unknown action {other}")),
+    }
+}
+
+fn add_todo(app: &AppHandle, query: &HashMap<String, String>) -> Result<(), String> {
+    let title = query
+        .get("title")
+        .map(|title| title.trim())
+        .filter(|title| !title.is_empty())
+        .ok_or_else(|| "missing title".to_string())?;
+
+    let reminder_at = query
+        .get("remind")
+        .map(|value| parse_date_time(value).ok_or_else(|| format!("invalid date {value}")))
+        .transpose()?;
+
+    let list_id = query
+        .get("list")
+        .map(|list| resolve_list_id(&app.state::<AppState>().snapshot(), list))
+        .transpose()?;
+
+    commands::create_todo(
+        title.to_string(),
+        query.get("details").cloned(),
+        reminder_at,
+        query.get("parent").cloned(),
+        list_id,
+        app.clone(),
+        app.state::<AppState>(),
+    )?;
+
+    window::show_overlay_window(app).map_err(|error| error.to_string())
+}
+
+fn open_todo(app: &AppHandle, query: &HashMap<String, String>) -> Result<(), String> {
+    let id = query
+        .get("todo")
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| "missing todo".to_string())?;
+
+    let list_id = app
+        .state::<AppState>()
+        .snapshot()
+        .todos
+        .into_iter()
+        .find(|todo| todo.id == id)
+        .ok_or_else(|| format!("unknown todo {id}"))?
+        .list_id;
+
+    if let Some(list_id) = list_id {
+        commands::set_active_list(list_id, app.clone(), app.state::<AppState>())?;
+    }
+
+    window::show_overlay_window(app).map_err(|error| error.to_string())?;
+    app.emit("open-todo", id).ok();
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! Todo events derived from saves, so that every mutation path (frontend,
+//! IPC, HTTP API, D-Bus) raises them without each command having to. The
+//! reminder scheduler raises `Reminder` itself. A save raising more than
+//! `MAX_FORWARDED_EVENTS` events, like an import or clearing the history, is
+//! logged rather than forwarded event by event to webhooks and script hooks.
+//!
+//! Receivers get an `EventPayload`, built from `PayloadTodo` rather than
+//! `Todo` so that changes to the storage format do not change what they see.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::hooks;
+use crate::storage::{now_millis, AppData, Settings, Todo, TodoEventKind, TodoPriority};
+use crate::webhooks;
+
+#[derive(Debug, Clone)]
+pub struct TodoEvent {
+    pub kind: TodoEventKind,
+    pub todo: Todo,
+    pub occurred_at: i64,
+}
+
+const PAYLOAD_VERSION: u32 = 1;
+const MAX_FORWARDED_EVENTS: usize = 50;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPayload {
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery_id: Option<String>,
+    pub event: &'static str,
+    pub occurred_at: i64,
+    pub todo: PayloadTodo,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadTodo {
+    pub id: String,
+    pub title: String,
+    pub details: Option<String>,
+    pub list_id: Option<String>,
+    pub list_name: Option<String>,
+    pub label_id: Option<String>,
+    pub label_name: Option<String>,
+    pub parent_id: Option<String>,
+    pub priority: TodoPriority,
+    pub starred: bool,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+    pub reminder_at: Option<i64>,
+    pub start_at: Option<i64>,
+}
+
+impl EventPayload {
+    pub fn new(event: &TodoEvent, settings: &Settings) -> Self {
+        let todo = &event.todo;
+        let list_name = settings
+            .lists
+            .iter()
+            .find(|list| todo.list_id.as_deref() == Some(list.id.as_str()))
+            .map(|list| list.name.clone());
+        let label_name = settings
+            .labels
+            .iter()
+            .find(|label| todo.label_id.as_deref() == Some(label.id.as_str()))
+            .map(|label| label.name.clone());
+
+        Self {
+            version: PAYLOAD_VERSION,
+            delivery_id: None,
+            event: event.kind.name(),
+            occurred_at: event.occurred_at,
+            todo: PayloadTodo {
+                id: todo.id.clone(),
+                title: todo.title.clone(),
+                details: todo.details.clone(),
+                list_id: todo.list_id.clone(),
+                list_name,
+                label_id: todo.label_id.clone(),
+                label_name,
+                parent_id: todo.parent_id.clone(),
+                priority: todo.priority,
+                starred: todo.starred,
+                created_at: todo.created_at,
+                completed_at: todo.completed_at,
+                reminder_at: todo.reminder_at,
+                start_at: todo.start_at,
+            },
+        }
+    }
+}
+
+/// Todos as of the last save, to tell what the next one changed.
+pub struct EventTracker {
+    todos: Mutex<HashMap<String, Todo>>,
+}
+
+impl EventTracker {
+    pub fn new(data: &AppData) -> Self {
+        Self {
+            todos: Mutex::new(index(&data.todos)),
+        }
+    }
+}
+
+/// Raises the events between the previous save and `data`.
+pub fn publish_change(app: &AppHandle, data: &AppData) {
+    let Some(tracker) = app.try_state::<EventTracker>() else {
+        return;
+    };
+
+    let events = {
+        let Ok(mut todos) = tracker.todos.lock() else {
+            return;
+        };
+        let events = diff(&todos, &data.todos, now_millis());
+        *todos = index(&data.todos);
+        events
+    };
+
+    dispatch(app, &data.settings, &events);
+}
+
+/// Raises a `Reminder` event once the reminder notification was shown.
+pub fn publish_reminder(app: &AppHandle, settings: &Settings, todo: &Todo) {
+    let event = TodoEvent {
+        kind: TodoEventKind::Reminder,
+        todo: todo.clone(),
+        occurred_at: now_millis(),
+    };
+
+    dispatch(app, settings, &[event]);
+}
+
+fn dispatch(app: &AppHandle, settings: &Settings, events: &[TodoEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    if events.len() > MAX_FORWARDED_EVENTS {
+        log::info!(
+            "{} todo events raised by one save were not forwarded to webhooks and script hooks",
+            events.len()
+        );
+        return;
+    }
+
+    webhooks::enqueue(app, settings, events);
+    hooks::enqueue(app, settings, events);
+}
+
+fn index(todos: &[Todo]) -> HashMap<String, Todo> {
+    todos
+        .iter()
+        .map(|todo| (todo.id.clone(), todo.clone()))
+        .collect()
+}
+
+fn diff(before: &HashMap<String, Todo>, after: &[Todo], now: i64) -> Vec<TodoEvent> {
+    let mut events = Vec::new();
+    let mut push = |kind, todo: &Todo| {
+        events.push(TodoEvent {
+            kind,
+            todo: todo.clone(),
+            occurred_at: now,
+        })
+    };
+
+    for todo in after {
+        let previous = before.get(&todo.id);
+        if previous.is_none() {
+            push(TodoEventKind::Created, todo);
+        }
+
+        let was_completed = previous.is_some_and(|previous| previous.completed_at.is_some());
+        if todo.completed_at.is_some() && !was_completed {
+            push(TodoEventKind::Completed, todo);
+        }
+    }
+
+    let remaining = after
+        .iter()
+        .map(|todo| todo.id.as_str())
+        .collect::<HashSet<_>>();
+    for todo in before.values() {
+        if !remaining.contains(todo.id.as_str()) {
+            push(TodoEventKind::Deleted, todo);
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: &str, completed_at: Option<i64>) -> Todo {
+        Todo {
+            created_at: 1,
+            completed_at,
+            ..Todo::for_test(id, id)
+        }
+    }
+
+    #[test]
+    fn detects_created_completed_and_deleted_todos() {
+        let before = index(&[todo("kept", None), todo("done", None), todo("gone", None)]);
+        let after = [todo("kept", None), todo("done", Some(5)), todo("new", None)];
+
+        let mut events = diff(&before, &after, 10)
+            .into_iter()
+            .map(|event| (event.kind, event.todo.id))
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| a.1.cmp(&b.1));
+
+        assert_eq!(
+            events,
+            vec![
+                (TodoEventKind::Completed, "done".to_string()),
+                (TodoEventKind::Deleted, "gone".to_string()),
+                (TodoEventKind::Created, "new".to_string()),
+            ]
+        );
+    }
+}
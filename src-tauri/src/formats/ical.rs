@@ -0,0 +1,671 @@
+//! iCalendar (RFC 5545) `VTODO` components, for calendar and task apps:
+//!
+//! - `SUMMARY`, `DESCRIPTION` and `CATEGORIES` (the label) are text.
+//! - `PRIORITY` is 1 (urgent), 3 (high), 5 (medium) or 7 (low); on import,
+//!   1–2, 3–4, 5 and 6–9 map back to the same priorities.
+//! - `STATUS:COMPLETED` and `COMPLETED` carry the completion time.
+//! - The reminder is written both as `DUE` and as a display `VALARM`. On
+//!   import the first alarm wins, with relative triggers resolved against
+//!   `DUE` or `DTSTART`, and `DUE` is the fallback.
+//! - `DTSTART` is the start date and `RELATED-TO` the parent todo's `UID`.
+//!
+//! Times are written in UTC. Times with a `TZID` are read as local time, as
+//! zone definitions are not applied, and dates alone as 09:00.
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use tauri::{AppHandle, State};
+
+use super::{attach, export_lists, insert_tree, list_trees, ImportedTodo, TodoTree};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, AppData, AppState, TodoPriority};
+
+const PRODUCT_ID: &str = "-//BlinkDo//BlinkDo//EN";
+// Content lines are folded after this many octets.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Exports one list, or every list when `list_id` is `None`.
+#[tauri::command]
+pub fn export_ical(list_id: Option<String>, state: State<'_, AppState>) -> Result<String, String> {
+    let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+    to_ical(&data, list_id.as_deref(), now_millis())
+}
+
+/// Adds the `VTODO` components of `content` to `list_id`. Events and other
+/// components are ignored.
+#[tauri::command]
+pub fn import_ical(
+    content: String,
+    list_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let todos = parse(&content);
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let count = insert_tree(&mut guard, &list_id, todos)?;
+        log::info!("imported {count} todos from iCalendar into {list_id}");
+    }
+
+    persist_state(&app, &state)
+}
+
+/// `now` is the `DTSTAMP` of every component.
+pub fn to_ical(data: &AppData, list_id: Option<&str>, now: i64) -> Result<String, String> {
+    let lists = export_lists(data, list_id)?;
+
+    let mut output = String::new();
+    push_line(&mut output, "BEGIN:VCALENDAR");
+    push_line(&mut output, "VERSION:2.0");
+    push_line(&mut output, &format!("PRODID:{PRODUCT_ID}"));
+    if let [list] = lists.as_slice() {
+        push_line(&mut output, &format!("X-WR-CALNAME:{}", escape(&list.name)));
+    }
+
+    let stamp = format_utc(now).unwrap_or_default();
+    for list in lists {
+        for tree in list_trees(data, &list.id) {
+            write_tree(&mut output, data, &tree, None, &stamp);
+        }
+    }
+
+    push_line(&mut output, "END:VCALENDAR");
+    Ok(output)
+}
+
+fn write_tree(
+    output: &mut String,
+    data: &AppData,
+    tree: &TodoTree,
+    parent_id: Option<&str>,
+    stamp: &str,
+) {
+    let todo = tree.todo;
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", todo.id),
+        format!("DTSTAMP:{stamp}"),
+    ];
+
+    if let Some(created) = format_utc(todo.created_at) {
+        lines.push(format!("CREATED:{created}"));
+    }
+    lines.push(format!("SUMMARY:{}", escape(&todo.title)));
+    if let Some(details) = &todo.details {
+        lines.push(format!("DESCRIPTION:{}", escape(details)));
+    }
+    if let Some(priority) = priority_value(todo.priority) {
+        lines.push(format!("PRIORITY:{priority}"));
+    }
+    if let Some(label) = todo
+        .label_id
+        .as_deref()
+        .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+    {
+        lines.push(format!("CATEGORIES:{}", escape(&label.name)));
+    }
+    if let Some(start) = todo.start_at.and_then(format_utc) {
+        lines.push(format!("DTSTART:{start}"));
+    }
+    match todo.completed_at.and_then(format_utc) {
+        Some(completed) => {
+            lines.push("STATUS:COMPLETED".to_string());
+            lines.push(format!("COMPLETED:{completed}"));
+            lines.push("PERCENT-COMPLETE:100".to_string());
+        }
+        None => lines.push("STATUS:NEEDS-ACTION".to_string()),
+    }
+    if let Some(parent_id) = parent_id {
+        lines.push(format!("RELATED-TO;RELTYPE=PARENT:{parent_id}"));
+    }
+    if let Some(reminder) = todo.reminder_at.and_then(format_utc) {
+        lines.push(format!("DUE:{reminder}"));
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape(&todo.title)));
+        lines.push(format!("TRIGGER;VALUE=DATE-TIME:{reminder}"));
+        lines.push("END:VALARM".to_string());
+    }
+    lines.push("END:VTODO".to_string());
+
+    for line in lines {
+        push_line(output, &line);
+    }
+
+    for child in &tree.children {
+        write_tree(output, data, child, Some(&todo.id), stamp);
+    }
+}
+
+/// Ends `line` with CRLF, folding it into continuation lines as needed.
+fn push_line(output: &mut String, line: &str) {
+    let mut octets = 0;
+    for character in line.chars() {
+        if octets + character.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            octets = 1;
+        }
+        output.push(character);
+        octets += character.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// The todos of every `VTODO` in `content`, nested by `RELATED-TO`.
+pub fn parse(content: &str) -> Vec<ImportedTodo> {
+    let mut components: Vec<Component> = Vec::new();
+    let mut current: Option<Component> = None;
+    let mut alarm: Option<Alarm> = None;
+
+    for line in unfold(content) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+
+        match (
+            property.name.as_str(),
+            property.value.to_ascii_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VTODO") => current = Some(Component::default()),
+            ("END", "VTODO") => components.extend(current.take()),
+            ("BEGIN", "VALARM") if current.is_some() => alarm = Some(Alarm::default()),
+            ("END", "VALARM") => {
+                if let (Some(component), Some(alarm)) = (current.as_mut(), alarm.take()) {
+                    component.alarms.push(alarm);
+                }
+            }
+            _ => match (alarm.as_mut(), current.as_mut()) {
+                (Some(alarm), _) => alarm.read(property),
+                (None, Some(component)) => component.read(property),
+                (None, None) => {}
+            },
+        }
+    }
+
+    let now = now_millis();
+    let parents = components
+        .iter()
+        .map(|component| {
+            let parent_uid = component.parent_uid.as_deref()?;
+            components
+                .iter()
+                .position(|other| other.uid.as_deref() == Some(parent_uid))
+        })
+        .collect::<Vec<_>>();
+    let todos = components
+        .into_iter()
+        .map(|component| component.into_todo(now))
+        .collect();
+
+    attach(todos, &parents)
+}
+
+#[derive(Default)]
+struct Component {
+    uid: Option<String>,
+    parent_uid: Option<String>,
+    todo: ImportedTodo,
+    completed: bool,
+    due_at: Option<i64>,
+    alarms: Vec<Alarm>,
+}
+
+impl Component {
+    fn read(&mut self, property: Property) {
+        match property.name.as_str() {
+            "UID" => self.uid = Some(property.value),
+            "SUMMARY" => self.todo.title = unescape(&property.value),
+            "DESCRIPTION" => self.todo.details = Some(unescape(&property.value)),
+            "PRIORITY" => {
+                self.todo.priority = property
+                    .value
+                    .trim()
+                    .parse()
+                    .map(parse_priority)
+                    .unwrap_or_default();
+            }
+            "CATEGORIES" => {
+                self.todo.label = split_values(&property.value)
+                    .into_iter()
+                    .find(|category| !category.trim().is_empty());
+            }
+            "STATUS" => self.completed = property.value.eq_ignore_ascii_case("COMPLETED"),
+            "COMPLETED" => self.todo.completed_at = property.time(),
+            "CREATED" => self.todo.created_at = property.time(),
+            "DTSTART" => self.todo.start_at = property.time(),
+            "DUE" => self.due_at = property.time(),
+            "RELATED-TO"
+                if property
+                    .param("RELTYPE")
+                    .map_or(true, |reltype| reltype.eq_ignore_ascii_case("PARENT")) =>
+            {
+                self.parent_uid = Some(property.value);
+            }
+            _ => {}
+        }
+    }
+
+    fn into_todo(self, now: i64) -> ImportedTodo {
+        let mut todo = self.todo;
+        if self.completed && todo.completed_at.is_none() {
+            todo.completed_at = Some(now);
+        }
+
+        let start_at = todo.start_at;
+        let due_at = self.due_at;
+        todo.reminder_at = self
+            .alarms
+            .iter()
+            .find_map(|alarm| match alarm.trigger? {
+                Trigger::At(at) => Some(at),
+                Trigger::FromStart(offset) => {
+                    start_at.or(due_at).and_then(|at| at.checked_add(offset))
+                }
+                Trigger::FromEnd(offset) => due_at.and_then(|at| at.checked_add(offset)),
+            })
+            .or(due_at);
+        todo
+    }
+}
+
+#[derive(Default)]
+struct Alarm {
+    trigger: Option<Trigger>,
+}
+
+impl Alarm {
+    fn read(&mut self, property: Property) {
+        if property.name != "TRIGGER" {
+            return;
+        }
+
+        let absolute = property
+            .param("VALUE")
+            .is_some_and(|value| value.eq_ignore_ascii_case("DATE-TIME"));
+        self.trigger = if absolute {
+            property.time().map(Trigger::At)
+        } else {
+            let offset = parse_duration(&property.value);
+            if property
+                .param("RELATED")
+                .is_some_and(|related| related.eq_ignore_ascii_case("END"))
+            {
+                offset.map(Trigger::FromEnd)
+            } else {
+                offset.map(Trigger::FromStart)
+            }
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Trigger {
+    At(i64),
+    FromStart(i64),
+    FromEnd(i64),
+}
+
+/// One content line: `NAME;PARAM=value:VALUE`, with the name uppercased.
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        let mut in_quotes = false;
+        let colon = line.char_indices().find_map(|(index, character)| {
+            match character {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => return Some(index),
+                _ => {}
+            }
+            None
+        })?;
+
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = split_outside_quotes(head, ';').into_iter();
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        if name.is_empty() {
+            return None;
+        }
+        let params = parts
+            .filter_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                Some((
+                    key.trim().to_ascii_uppercase(),
+                    value.trim().trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn time(&self) -> Option<i64> {
+        let value = self.value.trim();
+        let date_only = self
+            .param("VALUE")
+            .is_some_and(|kind| kind.eq_ignore_ascii_case("DATE"))
+            || value.len() == 8;
+
+        if date_only {
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+            return local_millis(date.and_hms_opt(9, 0, 0)?);
+        }
+
+        if let Some(utc) = value.strip_suffix('Z') {
+            let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            return Some(Utc.from_utc_datetime(&naive).timestamp_millis());
+        }
+
+        local_millis(NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?)
+    }
+}
+
+/// Joins folded lines, accepting bare LF line endings too.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+fn split_outside_quotes(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (index, character) in text.char_indices() {
+        if character == '"' {
+            in_quotes = !in_quotes;
+        } else if character == separator && !in_quotes {
+            parts.push(&text[start..index]);
+            start = index + 1;
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Splits a list of text values on unescaped commas and unescapes them.
+fn split_values(value: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => {
+                current.push('\\');
+                current.extend(characters.next());
+            }
+            ',' => values.push(unescape(&std::mem::take(&mut current))),
+            _ => current.push(character),
+        }
+    }
+    values.push(unescape(&current));
+    values
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// `[+-]P[nW][nD][T[nH][nM][nS]]` in milliseconds.
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut seconds = 0i64;
+    let mut in_time = false;
+    let mut parsed_any = false;
+
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let amount = rest[..digits].parse::<i64>().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        rest = &rest[digits + unit.len_utf8()..];
+
+        let unit_seconds = match (unit, in_time) {
+            ('W', false) => 7 * 86_400,
+            ('D', false) => 86_400,
+            ('H', true) => 3_600,
+            ('M', true) => 60,
+            ('S', true) => 1,
+            _ => return None,
+        };
+        seconds = amount
+            .checked_mul(unit_seconds)
+            .and_then(|amount| seconds.checked_add(amount))?;
+        parsed_any = true;
+    }
+
+    // `None` on overflow, as for any other invalid duration.
+    parsed_any
+        .then(|| seconds.checked_mul(sign * 1_000))
+        .flatten()
+}
+
+fn local_millis(naive: NaiveDateTime) -> Option<i64> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+fn format_utc(millis: i64) -> Option<String> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|date_time| date_time.format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+fn priority_value(priority: TodoPriority) -> Option<u8> {
+    match priority {
+        TodoPriority::None => None,
+        TodoPriority::Urgent => Some(1),
+        TodoPriority::High => Some(3),
+        TodoPriority::Medium => Some(5),
+        TodoPriority::Low => Some(7),
+    }
+}
+
+/// 0 is undefined; 1 is the highest priority and 9 the lowest.
+fn parse_priority(value: u8) -> TodoPriority {
+    match value {
+        1..=2 => TodoPriority::Urgent,
+        3..=4 => TodoPriority::High,
+        5 => TodoPriority::Medium,
+        6..=9 => TodoPriority::Low,
+        _ => TodoPriority::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Todo, TodoLabel, DEFAULT_LIST_ID};
+
+    const EXPORT_FIXTURE: &str = include_str!("fixtures/export.ics");
+    const IMPORT_FIXTURE: &str = include_str!("fixtures/import.ics");
+
+    // 2026-03-01T09:00:00Z
+    const BASE_MILLIS: i64 = 1_772_355_600_000;
+    const HOUR_MILLIS: i64 = 3_600_000;
+
+    fn todo(id: &str, title: &str) -> Todo {
+        Todo {
+            sort_index: Some(0),
+            created_at: BASE_MILLIS,
+            ..Todo::for_test(id, title)
+        }
+    }
+
+    fn sample_data() -> AppData {
+        let mut data = AppData::default();
+        data.settings.lists[0].name = "Work".to_string();
+        data.settings.labels.push(TodoLabel {
+            id: "label-release".to_string(),
+            name: "Release, QA".to_string(),
+            color: "slate".to_string(),
+        });
+
+        data.todos.push(Todo {
+            details: Some("Check the notes; then tag.\nPing the team".to_string()),
+            priority: TodoPriority::High,
+            label_id: Some("label-release".to_string()),
+            reminder_at: Some(BASE_MILLIS + 24 * HOUR_MILLIS),
+            start_at: Some(BASE_MILLIS + 2 * HOUR_MILLIS),
+            ..todo("todo-release", "Prepare the release")
+        });
+        data.todos.push(Todo {
+            parent_id: Some("todo-release".to_string()),
+            priority: TodoPriority::Urgent,
+            completed_at: Some(BASE_MILLIS + 3 * HOUR_MILLIS),
+            ..todo("todo-changelog", "Update the changelog")
+        });
+        data.todos.push(Todo {
+            sort_index: Some(1),
+            ..todo(
+                "todo-long",
+                "A title long enough to be folded over two content lines by the writer",
+            )
+        });
+        data
+    }
+
+    #[test]
+    fn exports_the_fixture() {
+        let exported = to_ical(&sample_data(), Some(DEFAULT_LIST_ID), BASE_MILLIS)
+            .expect("default list exists");
+
+        assert_eq!(exported, EXPORT_FIXTURE);
+    }
+
+    #[test]
+    fn reads_its_own_export_back() {
+        let todos = parse(EXPORT_FIXTURE);
+
+        assert_eq!(todos.len(), 2);
+        let release = &todos[0];
+        assert_eq!(release.title, "Prepare the release");
+        assert_eq!(
+            release.details.as_deref(),
+            Some("Check the notes; then tag.\nPing the team")
+        );
+        assert_eq!(release.priority, TodoPriority::High);
+        assert_eq!(release.label.as_deref(), Some("Release, QA"));
+        assert_eq!(release.created_at, Some(BASE_MILLIS));
+        assert_eq!(release.start_at, Some(BASE_MILLIS + 2 * HOUR_MILLIS));
+        assert_eq!(release.reminder_at, Some(BASE_MILLIS + 24 * HOUR_MILLIS));
+        assert_eq!(release.children.len(), 1);
+        assert_eq!(release.children[0].priority, TodoPriority::Urgent);
+        assert_eq!(
+            release.children[0].completed_at,
+            Some(BASE_MILLIS + 3 * HOUR_MILLIS)
+        );
+        assert_eq!(
+            todos[1].title,
+            "A title long enough to be folded over two content lines by the writer"
+        );
+    }
+
+    #[test]
+    fn imports_the_fixture_from_other_apps() {
+        let todos = parse(IMPORT_FIXTURE);
+
+        // The subtask comes first in the file and the event is skipped.
+        assert_eq!(todos.len(), 2);
+        let groceries = &todos[0];
+        assert_eq!(groceries.title, "Groceries");
+        assert_eq!(groceries.priority, TodoPriority::Low);
+        assert_eq!(groceries.label.as_deref(), Some("Home"));
+        // 15 minutes before the due time.
+        assert_eq!(
+            groceries.reminder_at,
+            Some(BASE_MILLIS + 24 * HOUR_MILLIS - 15 * 60_000)
+        );
+        assert_eq!(groceries.children.len(), 1);
+        assert_eq!(groceries.children[0].title, "Milk, eggs");
+        assert!(groceries.children[0].completed_at.is_some());
+
+        let call = &todos[1];
+        assert_eq!(call.title, "Call the plumber about the kitchen sink");
+        assert_eq!(
+            call.details.as_deref(),
+            Some("Number: 555-0100\nAsk for Sam")
+        );
+        assert_eq!(call.priority, TodoPriority::Urgent);
+        assert_eq!(
+            call.reminder_at,
+            local_millis(
+                NaiveDate::from_ymd_opt(2026, 3, 4)
+                    .and_then(|date| date.and_hms_opt(9, 0, 0))
+                    .expect("valid date")
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert_eq!(parse_duration("-PT15M"), Some(-900_000));
+        assert_eq!(parse_duration("P1DT2H"), Some(93_600_000));
+        assert_eq!(parse_duration("P99999999999999W"), None);
+        assert_eq!(parse_duration("PT9223372036854775807S"), None);
+    }
+}
@@ -0,0 +1,413 @@
+//! GitHub-style Markdown checklists, one `# List` section per list:
+//!
+//! ```markdown
+//! # Work
+//!
+//! - [ ] Prepare the release !high #Deep_work remind:2026-03-02T09:00
+//!   Details are indented under their todo.
+//!   - [x] Update the changelog
+//! ```
+//!
+//! Priority (`!low` … `!urgent`), label (`#Name`, spaces written as `_`) and
+//! reminder (`remind:<local date-time>`) are trailing tokens of the title.
+//! A title ending with a word that reads as a token, like `Fix issue #42`, has
+//! it escaped as `\#42`. Subtasks are nested list items; a detail line that
+//! reads as one, like `- buy milk`, is escaped as `\- buy milk`.
+
+use tauri::{AppHandle, State};
+
+use super::{
+    build_tree, export_lists, format_local, insert_tree, list_trees, ImportedTodo, TodoTree,
+};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, parse_date_time, AppData, AppState, TodoPriority};
+
+const INDENT: &str = "  ";
+
+/// Exports one list, or every list when `list_id` is `None`.
+#[tauri::command]
+pub fn export_markdown(
+    list_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+    to_markdown(&data, list_id.as_deref())
+}
+
+/// Adds the checklist items of `content` to `list_id`. Headings are ignored,
+/// so a multi-list export lands in a single list.
+#[tauri::command]
+pub fn import_markdown(
+    content: String,
+    list_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let todos = parse(&content);
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let count = insert_tree(&mut guard, &list_id, todos)?;
+        log::info!("imported {count} todos from Markdown into {list_id}");
+    }
+
+    persist_state(&app, &state)
+}
+
+pub fn to_markdown(data: &AppData, list_id: Option<&str>) -> Result<String, String> {
+    let lists = export_lists(data, list_id)?;
+
+    let mut output = String::new();
+    for list in lists {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("# {}\n\n", list.name));
+
+        for tree in list_trees(data, &list.id) {
+            write_tree(&mut output, data, &tree, 0);
+        }
+    }
+
+    Ok(output)
+}
+
+fn write_tree(output: &mut String, data: &AppData, tree: &TodoTree, depth: usize) {
+    let todo = tree.todo;
+    let indent = INDENT.repeat(depth);
+    let check = if todo.completed_at.is_some() {
+        'x'
+    } else {
+        ' '
+    };
+
+    let mut line = format!("{indent}- [{check}] {}", escape_title(&todo.title));
+    if let Some(token) = priority_token(todo.priority) {
+        line.push_str(&format!(" !{token}"));
+    }
+    if let Some(label) = todo
+        .label_id
+        .as_deref()
+        .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+    {
+        line.push_str(&format!(
+            " #{}",
+            label.name.split_whitespace().collect::<Vec<_>>().join("_")
+        ));
+    }
+    if let Some(reminder) = todo.reminder_at.and_then(format_local) {
+        line.push_str(&format!(" remind:{reminder}"));
+    }
+    output.push_str(&line);
+    output.push('\n');
+
+    if let Some(details) = &todo.details {
+        for detail in details.lines() {
+            if detail.trim().is_empty() {
+                output.push('\n');
+            } else {
+                output.push_str(&format!("{indent}{INDENT}{}\n", escape_detail(detail)));
+            }
+        }
+    }
+
+    for child in &tree.children {
+        write_tree(output, data, child, depth + 1);
+    }
+}
+
+/// Parses the checklist items of `content`. Plain list items without a
+/// checkbox are accepted as open todos.
+pub fn parse(content: &str) -> Vec<ImportedTodo> {
+    let now = now_millis();
+    let mut items: Vec<(usize, ImportedTodo)> = Vec::new();
+    let mut pending_blank_lines = 0;
+
+    for raw_line in content.lines() {
+        let line = raw_line.replace('\t', "    ");
+        // In characters, as lines may be indented with e.g. U+3000.
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        let text = line.trim();
+
+        if text.is_empty() {
+            pending_blank_lines += 1;
+            continue;
+        }
+
+        if let Some((checked, title)) = parse_item(text) {
+            let mut todo = parse_title(title);
+            if checked {
+                todo.completed_at = Some(now);
+            }
+            items.push((indent, todo));
+            pending_blank_lines = 0;
+            continue;
+        }
+
+        // Indented text under an item is part of its details.
+        let Some((item_indent, todo)) = items.last_mut() else {
+            continue;
+        };
+        if indent <= *item_indent {
+            pending_blank_lines = 0;
+            continue;
+        }
+
+        let content_indent = (*item_indent + INDENT.len()).min(indent);
+        let detail = line
+            .char_indices()
+            .nth(content_indent)
+            .map_or("", |(offset, _)| &line[offset..]);
+        let details = todo.details.get_or_insert_with(String::new);
+        if !details.is_empty() {
+            details.push_str(&"\n".repeat(pending_blank_lines + 1));
+        }
+        details.push_str(&unescape_detail(detail.trim_end()));
+        pending_blank_lines = 0;
+    }
+
+    build_tree(items)
+}
+
+/// Escapes a detail line that would be read as a list item, or is one already
+/// escaped, so that it stays part of the details.
+fn escape_detail(detail: &str) -> String {
+    let text = detail.trim_start();
+    if parse_item(text.trim_end()).is_some() || is_escaped_item(text.trim_end()) {
+        let offset = detail.len() - text.len();
+        format!("{}\\{text}", &detail[..offset])
+    } else {
+        detail.to_string()
+    }
+}
+
+fn unescape_detail(detail: &str) -> String {
+    let text = detail.trim_start();
+    if is_escaped_item(text) {
+        let offset = detail.len() - text.len();
+        format!("{}{}", &detail[..offset], &text[1..])
+    } else {
+        detail.to_string()
+    }
+}
+
+/// A list item behind one or more backslashes.
+fn is_escaped_item(text: &str) -> bool {
+    text.starts_with('\\') && parse_item(text.trim_start_matches('\\')).is_some()
+}
+
+/// `- [ ] title`, `* [x] title`, `1. title`: whether it is checked, and the
+/// rest of the line.
+pub(crate) fn parse_item(text: &str) -> Option<(bool, &str)> {
+    let rest = if let Some(rest) = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("* "))
+        .or_else(|| text.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = text.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        text[digits..]
+            .strip_prefix(". ")
+            .or_else(|| text[digits..].strip_prefix(") "))?
+    };
+
+    let rest = rest.trim_start();
+    if let Some(title) = rest.strip_prefix("[ ]") {
+        Some((false, title.trim_start()))
+    } else if let Some(title) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        Some((true, title.trim_start()))
+    } else {
+        Some((false, rest))
+    }
+}
+
+/// Splits the trailing metadata tokens off a title.
+fn parse_title(title: &str) -> ImportedTodo {
+    let mut words = title.split_whitespace().collect::<Vec<_>>();
+    let mut todo = ImportedTodo::default();
+
+    while let Some(word) = words.last() {
+        if let Some(priority) = word.strip_prefix('!').and_then(parse_priority) {
+            todo.priority = priority;
+        } else if let Some(label) = word.strip_prefix('#').filter(|label| !label.is_empty()) {
+            todo.label = Some(label.replace('_', " "));
+        } else if let Some(reminder) = word.strip_prefix("remind:").and_then(parse_date_time) {
+            todo.reminder_at = Some(reminder);
+        } else {
+            break;
+        }
+        words.pop();
+    }
+
+    todo.title = words.join(" ");
+    if let Some(last) = words.last().filter(|word| is_escaped_token(word)) {
+        let unescaped = &last[1..];
+        todo.title = format!(
+            "{}{unescaped}",
+            &todo.title[..todo.title.len() - last.len()]
+        );
+    }
+    todo
+}
+
+/// Whether `word` would be read as a trailing token of a title.
+fn is_token(word: &str) -> bool {
+    word.strip_prefix('!').and_then(parse_priority).is_some()
+        || word
+            .strip_prefix('#')
+            .is_some_and(|label| !label.is_empty())
+        || word
+            .strip_prefix("remind:")
+            .and_then(parse_date_time)
+            .is_some()
+}
+
+/// A token-like word behind one or more backslashes.
+fn is_escaped_token(word: &str) -> bool {
+    word.starts_with('\\') && is_token(word.trim_start_matches('\\'))
+}
+
+/// Escapes the last word of `title` when it would be read as a token, or is
+/// one already escaped, so that it stays part of the title.
+fn escape_title(title: &str) -> String {
+    match title.split_whitespace().last() {
+        Some(last) if is_token(last) || is_escaped_token(last) => {
+            let (start, _) = title.rsplit_once(last).unwrap_or_default();
+            format!("{start}\\{last}")
+        }
+        _ => title.to_string(),
+    }
+}
+
+pub(crate) fn priority_token(priority: TodoPriority) -> Option<&'static str> {
+    match priority {
+        TodoPriority::None => None,
+        TodoPriority::Low => Some("low"),
+        TodoPriority::Medium => Some("medium"),
+        TodoPriority::High => Some("high"),
+        TodoPriority::Urgent => Some("urgent"),
+    }
+}
+
+pub(crate) fn parse_priority(token: &str) -> Option<TodoPriority> {
+    match token.to_ascii_lowercase().as_str() {
+        "low" => Some(TodoPriority::Low),
+        "medium" => Some(TodoPriority::Medium),
+        "high" => Some(TodoPriority::High),
+        "urgent" => Some(TodoPriority::Urgent),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Todo, DEFAULT_LIST_ID};
+
+    fn export_default_list(data: &AppData) -> String {
+        to_markdown(data, Some(DEFAULT_LIST_ID)).expect("default list exists")
+    }
+
+    #[test]
+    fn round_trips_hierarchy_details_and_tokens() {
+        let source = "\
+# My tasks
+
+- [ ] Prepare the release !high #Deep_work remind:2026-03-02T09:00
+  First line of details
+
+  Second paragraph
+  - [x] Update the changelog
+  - [ ] Tag the build !urgent
+- [ ] Call the bank
+";
+        let mut data = AppData::default();
+        let count = insert_tree(&mut data, DEFAULT_LIST_ID, parse(source)).expect("list exists");
+        assert_eq!(count, 4);
+
+        let release = data
+            .todos
+            .iter()
+            .find(|todo| todo.title == "Prepare the release")
+            .expect("imported");
+        assert_eq!(release.priority, TodoPriority::High);
+        assert_eq!(
+            release.details.as_deref(),
+            Some("First line of details\n\nSecond paragraph")
+        );
+        let children = data
+            .todos
+            .iter()
+            .filter(|todo| todo.parent_id.as_deref() == Some(release.id.as_str()))
+            .collect::<Vec<&Todo>>();
+        assert_eq!(children.len(), 2);
+        assert!(children[0].completed_at.is_some());
+        assert_eq!(children[1].sort_index, Some(1));
+
+        assert_eq!(export_default_list(&data), source);
+    }
+
+    #[test]
+    fn accepts_plain_and_numbered_items() {
+        let todos = parse("1. First\n2. Second\n   * [X] Nested\nNot an item\n");
+
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[1].children[0].title, "Nested");
+        assert!(todos[1].children[0].completed_at.is_some());
+    }
+
+    #[test]
+    fn keeps_token_like_words_and_wide_indents_in_titles() {
+        let source = "\
+# My tasks
+
+- [ ] Fix issue \\#42 #Bugs
+- [ ] Write \\\\#notes
+- [ ] 准备发布
+\u{3000}\u{3000}\u{3000}详细说明
+";
+        let todos = parse(source);
+        assert_eq!(todos[0].title, "Fix issue #42");
+        assert_eq!(todos[0].label.as_deref(), Some("Bugs"));
+        assert_eq!(todos[1].title, "Write \\#notes");
+        assert_eq!(todos[2].details.as_deref(), Some("\u{3000}详细说明"));
+
+        let mut data = AppData::default();
+        insert_tree(&mut data, DEFAULT_LIST_ID, todos).expect("list exists");
+        data.todos[2].details = None;
+        assert_eq!(
+            export_default_list(&data),
+            source.replace("\u{3000}\u{3000}\u{3000}详细说明\n", "")
+        );
+    }
+
+    #[test]
+    fn keeps_bullets_in_details() {
+        let source = "\
+# My tasks
+
+- [ ] Shopping
+  \\- buy milk
+  \\* note
+    \\1. step
+  \\\\- literal
+  - [ ] Real subtask
+";
+        let todos = parse(source);
+        assert_eq!(
+            todos[0].details.as_deref(),
+            Some("- buy milk\n* note\n  1. step\n\\- literal")
+        );
+        assert_eq!(todos[0].children.len(), 1);
+
+        let mut data = AppData::default();
+        insert_tree(&mut data, DEFAULT_LIST_ID, todos).expect("list exists");
+        assert_eq!(export_default_list(&data), source);
+    }
+}
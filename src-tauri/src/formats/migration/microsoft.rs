@@ -0,0 +1,379 @@
+//! Microsoft To Do and Outlook. Outlook exports tasks as CSV with dates
+//! written month first, as by English Outlook. To Do tasks come from
+//! Microsoft Graph as JSON: a `value` array of tasks, or lists with their
+//! `tasks`; checklist steps become subtasks.
+
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{parse_timestamp, parse_utc_timestamp, CsvTable, ImportedList};
+use crate::formats::ImportedTodo;
+use crate::storage::{now_millis, TodoPriority};
+
+pub fn parse_outlook_csv(
+    content: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<ImportedList>, String> {
+    let table = CsvTable::parse(content)?;
+    let Some(subject) = table.column("subject") else {
+        return Err("not an Outlook task export: the Subject column is missing".to_string());
+    };
+    let notes = table.column("notes");
+    let priority = table.column("priority");
+    let status = table.column("status");
+    let categories = table.column("categories");
+    let start_date = table.column("start date");
+    let due_date = table.column("due date");
+    let reminder_on = table.column("reminder on/off");
+    let reminder_date = table.column("reminder date");
+    let reminder_time = table.column("reminder time");
+    let date_completed = table.column("date completed");
+
+    let mut todos = Vec::new();
+    for row in &table.rows {
+        let Some(title) = CsvTable::field(row, Some(subject)) else {
+            continue;
+        };
+        let mut date = |column: Option<usize>, time: Option<&str>| {
+            let value = CsvTable::field(row, column)?;
+            let parsed = outlook_timestamp(value, time);
+            if parsed.is_none() {
+                warnings.push(format!(
+                    "the date \"{value}\" of \"{title}\" was not understood"
+                ));
+            }
+            parsed
+        };
+
+        let reminder = CsvTable::field(row, reminder_on)
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+            .then(|| date(reminder_date, CsvTable::field(row, reminder_time)))
+            .flatten();
+        let mut todo = ImportedTodo {
+            title: title.to_string(),
+            details: CsvTable::field(row, notes).map(str::to_string),
+            priority: CsvTable::field(row, priority)
+                .map(parse_importance)
+                .unwrap_or_default(),
+            label: CsvTable::field(row, categories)
+                .and_then(|categories| {
+                    categories
+                        .split([';', ','])
+                        .map(str::trim)
+                        .find(|c| !c.is_empty())
+                })
+                .map(str::to_string),
+            start_at: date(start_date, None),
+            reminder_at: reminder.or_else(|| date(due_date, None)),
+            completed_at: date(date_completed, None),
+            ..ImportedTodo::default()
+        };
+        if todo.completed_at.is_none()
+            && CsvTable::field(row, status)
+                .is_some_and(|status| status.eq_ignore_ascii_case("completed"))
+        {
+            todo.completed_at = Some(now_millis());
+        }
+        todos.push(todo);
+    }
+
+    Ok(vec![ImportedList {
+        todos,
+        ..ImportedList::default()
+    }])
+}
+
+/// `M/D/YYYY` or ISO dates, with an optional `H:MM:SS AM` time.
+fn outlook_timestamp(date: &str, time: Option<&str>) -> Option<i64> {
+    let Ok(date) = NaiveDate::parse_from_str(date, "%m/%d/%Y") else {
+        return parse_timestamp(date);
+    };
+    let time = match time {
+        Some(time) => ["%I:%M:%S %p", "%I:%M %p", "%H:%M:%S", "%H:%M"]
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(time, format).ok())?,
+        None => NaiveTime::from_hms_opt(9, 0, 0)?,
+    };
+
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskList {
+    display_name: String,
+    #[serde(default)]
+    wellknown_list_name: Option<String>,
+    #[serde(default, alias = "value")]
+    tasks: Vec<Task>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Task {
+    title: String,
+    #[serde(default)]
+    body: Option<Body>,
+    #[serde(default)]
+    importance: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    is_reminder_on: bool,
+    #[serde(default)]
+    reminder_date_time: Option<GraphTime>,
+    #[serde(default)]
+    due_date_time: Option<GraphTime>,
+    #[serde(default)]
+    start_date_time: Option<GraphTime>,
+    #[serde(default)]
+    completed_date_time: Option<GraphTime>,
+    #[serde(default)]
+    created_date_time: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    checklist_items: Vec<ChecklistItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Body {
+    content: String,
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphTime {
+    date_time: String,
+    #[serde(default)]
+    time_zone: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecklistItem {
+    display_name: String,
+    #[serde(default)]
+    is_checked: bool,
+    #[serde(default)]
+    checked_date_time: Option<String>,
+}
+
+pub fn parse_todo_json(
+    content: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<ImportedList>, String> {
+    let document = serde_json::from_str::<Value>(content)
+        .map_err(|error| format!("not a Microsoft To Do export: {error}"))?;
+    let entries = match document {
+        Value::Array(entries) => entries,
+        Value::Object(mut object) => match object.remove("value") {
+            Some(Value::Array(entries)) => entries,
+            _ => vec![Value::Object(object)],
+        },
+        _ => return Err("not a Microsoft To Do export: expected tasks or lists".to_string()),
+    };
+
+    let mut lists = Vec::new();
+    let mut loose = Vec::new();
+    for entry in entries {
+        if entry.get("displayName").is_some() {
+            let list = serde_json::from_value::<TaskList>(entry)
+                .map_err(|error| format!("invalid To Do list: {error}"))?;
+            // "Tasks", the default list, goes to the list chosen for the import.
+            let project = (list.wellknown_list_name.as_deref() != Some("defaultList"))
+                .then_some(list.display_name);
+            lists.push(ImportedList {
+                project,
+                todos: list
+                    .tasks
+                    .into_iter()
+                    .map(|task| task_todo(task, warnings))
+                    .collect(),
+                ..ImportedList::default()
+            });
+        } else {
+            let task = serde_json::from_value::<Task>(entry)
+                .map_err(|error| format!("invalid To Do task: {error}"))?;
+            loose.push(task_todo(task, warnings));
+        }
+    }
+
+    if !loose.is_empty() {
+        lists.insert(
+            0,
+            ImportedList {
+                todos: loose,
+                ..ImportedList::default()
+            },
+        );
+    }
+    Ok(lists)
+}
+
+fn task_todo(task: Task, warnings: &mut Vec<String>) -> ImportedTodo {
+    let details = task.body.and_then(|body| {
+        let text = if body.content_type.as_deref() == Some("html") {
+            strip_tags(&body.content)
+        } else {
+            body.content
+        };
+        Some(text.trim().to_string()).filter(|text| !text.is_empty())
+    });
+    let reminder = task
+        .reminder_date_time
+        .filter(|_| task.is_reminder_on)
+        .and_then(|time| graph_timestamp(&time, false));
+    let due = task
+        .due_date_time
+        .and_then(|time| graph_timestamp(&time, true));
+
+    let mut todo = ImportedTodo {
+        title: task.title,
+        details,
+        priority: task
+            .importance
+            .as_deref()
+            .map(parse_importance)
+            .unwrap_or_default(),
+        label: task
+            .categories
+            .into_iter()
+            .find(|category| !category.trim().is_empty()),
+        created_at: task.created_date_time.as_deref().and_then(parse_timestamp),
+        start_at: task
+            .start_date_time
+            .and_then(|time| graph_timestamp(&time, true)),
+        reminder_at: reminder.or(due),
+        children: task
+            .checklist_items
+            .into_iter()
+            .map(|item| ImportedTodo {
+                title: item.display_name,
+                completed_at: item.is_checked.then(|| {
+                    item.checked_date_time
+                        .as_deref()
+                        .and_then(parse_timestamp)
+                        .unwrap_or_else(now_millis)
+                }),
+                ..ImportedTodo::default()
+            })
+            .collect(),
+        ..ImportedTodo::default()
+    };
+
+    if task.status.as_deref() == Some("completed") {
+        todo.completed_at = Some(
+            task.completed_date_time
+                .and_then(|time| graph_timestamp(&time, true))
+                .unwrap_or_else(now_millis),
+        );
+    }
+    if todo.title.trim().is_empty() {
+        warnings.push("a To Do task without a title was skipped".to_string());
+    }
+    todo
+}
+
+/// Graph times are in `timeZone`, usually UTC; other zones are read as
+/// local time. To Do stores due dates as midnight, read here as the date.
+fn graph_timestamp(time: &GraphTime, date_at_midnight: bool) -> Option<i64> {
+    let value = time.date_time.trim();
+    if date_at_midnight {
+        if let Some((date, "00:00:00" | "00:00:00.0000000")) = value.split_once('T') {
+            return parse_timestamp(date);
+        }
+    }
+
+    match time.time_zone.as_deref() {
+        None | Some("UTC" | "Etc/UTC" | "tzone://Microsoft/Utc") => parse_utc_timestamp(value),
+        Some(_) => parse_timestamp(value),
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for character in html.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(character),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn parse_importance(value: &str) -> TodoPriority {
+    match value.to_lowercase().as_str() {
+        "high" => TodoPriority::High,
+        "low" => TodoPriority::Low,
+        _ => TodoPriority::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_outlook_csv() {
+        let csv = "\
+\"Subject\",\"Start Date\",\"Due Date\",\"Reminder On/Off\",\"Reminder Date\",\"Reminder Time\",\"Date Completed\",\"Categories\",\"Notes\",\"Priority\",\"Status\"
+\"Send invoice\",\"3/2/2026\",\"3/5/2026\",\"True\",\"3/4/2026\",\"2:30:00 PM\",\"\",\"Billing;Work\",\"Net 30\",\"High\",\"Not Started\"
+\"Renew passport\",\"\",\"31/12/2026\",\"False\",\"\",\"\",\"\",\"\",\"\",\"Normal\",\"Completed\"
+";
+        let mut warnings = Vec::new();
+        let lists = parse_outlook_csv(csv, &mut warnings).expect("valid export");
+        let todos = &lists[0].todos;
+
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].priority, TodoPriority::High);
+        assert_eq!(todos[0].label.as_deref(), Some("Billing"));
+        assert_eq!(
+            todos[0].reminder_at,
+            outlook_timestamp("3/4/2026", Some("2:30:00 PM"))
+        );
+        assert!(todos[1].completed_at.is_some());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn reads_todo_lists_and_checklists() {
+        let json = r#"{"value": [
+            {"displayName": "Tasks", "wellknownListName": "defaultList", "tasks": [
+                {"title": "Water plants", "importance": "normal", "status": "notStarted"}
+            ]},
+            {"displayName": "Groceries", "wellknownListName": "none", "tasks": [
+                {"title": "Weekly shop", "importance": "high", "status": "notStarted",
+                 "body": {"content": "<p>Use the <b>list</b></p>", "contentType": "html"},
+                 "dueDateTime": {"dateTime": "2026-03-07T00:00:00.0000000", "timeZone": "UTC"},
+                 "categories": ["Home"],
+                 "checklistItems": [
+                    {"displayName": "Milk", "isChecked": true},
+                    {"displayName": "Bread", "isChecked": false}
+                 ]}
+            ]}
+        ]}"#;
+        let mut warnings = Vec::new();
+        let lists = parse_todo_json(json, &mut warnings).expect("valid export");
+
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0].project, None);
+        assert_eq!(lists[1].project.as_deref(), Some("Groceries"));
+        let shop = &lists[1].todos[0];
+        assert_eq!(shop.details.as_deref(), Some("Use the list"));
+        assert_eq!(shop.reminder_at, parse_timestamp("2026-03-07"));
+        assert_eq!(shop.children.len(), 2);
+        assert!(shop.children[0].completed_at.is_some());
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! Importers for the exports of other task managers. Each parser turns a file
+//! into `ImportedList`s; `import_tasks` applies them to a copy of the data,
+//! so that a dry run can report what an import would create before anything
+//! is saved.
+//!
+//! Projects become lists, found by name or created. Sections and headings
+//! become lists named `Project / Section`, as lists do not nest. Todos have a
+//! single label, the first one of the source; due dates become reminders.
+
+mod microsoft;
+mod things;
+mod todoist;
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use super::{insert_tree, resolve_list, ImportedTodo};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{AppData, AppState};
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSource {
+    /// Todoist project CSV export.
+    TodoistCsv,
+    /// Todoist backup (`projects`, `sections`, `items`, as in the Sync API).
+    TodoistJson,
+    /// Outlook task CSV export, which Microsoft To Do lists sync to.
+    OutlookCsv,
+    /// Microsoft Graph To Do tasks, or lists of them.
+    MicrosoftTodoJson,
+    /// Things JSON, as used by its `add-json` URL command.
+    ThingsJson,
+}
+
+/// Todos of one destination list. Without a project they go to the list
+/// chosen for the import.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedList {
+    pub project: Option<String>,
+    pub section: Option<String>,
+    pub todos: Vec<ImportedTodo>,
+}
+
+/// What an import created, or would create for a dry run.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub todos: usize,
+    pub lists: Vec<ListReport>,
+    pub labels_created: Vec<String>,
+    /// Data that could not be carried over, such as recurrences.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReport {
+    pub id: String,
+    pub name: String,
+    pub created: bool,
+    pub todos: usize,
+}
+
+/// Parses `content` and adds its todos, to `list_id` for those without a
+/// project. With `dry_run` nothing is saved and the report tells what would
+/// have been imported.
+#[tauri::command]
+pub fn import_tasks(
+    source: ImportSource,
+    content: String,
+    list_id: String,
+    dry_run: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, String> {
+    let (lists, warnings) = parse(source, &content)?;
+
+    let report = {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let mut preview = guard.clone();
+        let mut report = apply(&mut preview, lists, &list_id)?;
+        report.warnings = warnings;
+        report.dry_run = dry_run;
+        if !dry_run {
+            *guard = preview;
+        }
+        report
+    };
+
+    if !dry_run {
+        log::info!("imported {} todos from {source:?}", report.todos);
+        persist_state(&app, &state)?;
+    }
+    Ok(report)
+}
+
+/// The lists of `content` and warnings about what was dropped.
+pub fn parse(
+    source: ImportSource,
+    content: &str,
+) -> Result<(Vec<ImportedList>, Vec<String>), String> {
+    let mut warnings = Vec::new();
+    let lists = match source {
+        ImportSource::TodoistCsv => todoist::parse_csv(content, &mut warnings)?,
+        ImportSource::TodoistJson => todoist::parse_json(content, &mut warnings)?,
+        ImportSource::OutlookCsv => microsoft::parse_outlook_csv(content, &mut warnings)?,
+        ImportSource::MicrosoftTodoJson => microsoft::parse_todo_json(content, &mut warnings)?,
+        ImportSource::ThingsJson => things::parse_json(content, &mut warnings)?,
+    };
+    Ok((lists, warnings))
+}
+
+/// Adds `lists` to `data`. Lists are matched by name, case-insensitively.
+pub fn apply(
+    data: &mut AppData,
+    lists: Vec<ImportedList>,
+    list_id: &str,
+) -> Result<ImportReport, String> {
+    let fallback_name = data
+        .settings
+        .lists
+        .iter()
+        .find(|list| list.id == list_id)
+        .map(|list| list.name.clone())
+        .ok_or_else(|| format!("unknown list {list_id}"))?;
+    let existing_lists = data
+        .settings
+        .lists
+        .iter()
+        .map(|list| list.id.clone())
+        .collect::<HashSet<_>>();
+    let existing_labels = data.settings.labels.len();
+
+    let mut report = ImportReport::default();
+    for list in lists {
+        let project = list
+            .project
+            .as_deref()
+            .map(str::trim)
+            .filter(|project| !project.is_empty());
+        let name = match (project, list.section.as_deref().map(str::trim)) {
+            (project, Some(section)) if !section.is_empty() => {
+                Some(format!("{} / {section}", project.unwrap_or(&fallback_name)))
+            }
+            (project, _) => project.map(str::to_string),
+        };
+        let target = name
+            .as_deref()
+            .and_then(|name| resolve_list(data, name))
+            .unwrap_or_else(|| list_id.to_string());
+
+        let count = insert_tree(data, &target, list.todos)?;
+        report.todos += count;
+        match report.lists.iter_mut().find(|list| list.id == target) {
+            Some(list) => list.todos += count,
+            None => report.lists.push(ListReport {
+                name: data
+                    .settings
+                    .lists
+                    .iter()
+                    .find(|list| list.id == target)
+                    .map(|list| list.name.clone())
+                    .unwrap_or_default(),
+                created: !existing_lists.contains(&target),
+                id: target,
+                todos: count,
+            }),
+        }
+    }
+
+    report.labels_created = data.settings.labels[existing_labels..]
+        .iter()
+        .map(|label| label.name.clone())
+        .collect();
+    Ok(report)
+}
+
+/// RFC 3339, or a date and time without zone read as local time, or a date
+/// alone read as 09:00.
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.timestamp_millis());
+    }
+
+    naive_timestamp(value).and_then(|(naive, _)| {
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|date_time| date_time.timestamp_millis())
+    })
+}
+
+/// Like `parse_timestamp`, with times without zone read as UTC. Dates alone
+/// are still 09:00 local.
+fn parse_utc_timestamp(value: &str) -> Option<i64> {
+    match naive_timestamp(value.trim()) {
+        Some((naive, true)) => Some(Utc.from_utc_datetime(&naive).timestamp_millis()),
+        _ => parse_timestamp(value),
+    }
+}
+
+/// The date-time of `value` and whether it had a time of day.
+fn naive_timestamp(value: &str) -> Option<(NaiveDateTime, bool)> {
+    let with_time = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok());
+    if let Some(naive) = with_time {
+        return Some((naive, true));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(9, 0, 0))
+        .map(|naive| (naive, false))
+}
+
+/// Records of an RFC 4180 file, without the byte order mark some
+/// spreadsheets write.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut characters = content.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match (character, in_quotes) {
+            ('"', true) if characters.peek() == Some(&'"') => {
+                field.push('"');
+                characters.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(character),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records.retain(|record| record.iter().any(|field| !field.trim().is_empty()));
+    records
+}
+
+/// CSV records as rows addressed by header name, case-insensitively.
+struct CsvTable {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut records = parse_csv(content).into_iter();
+        let header = records
+            .next()
+            .ok_or_else(|| "the file is empty".to_string())?
+            .into_iter()
+            .map(|name| name.trim().to_lowercase())
+            .collect();
+        Ok(Self {
+            header,
+            rows: records.collect(),
+        })
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|header| header == name)
+    }
+
+    /// The trimmed, non-empty field of `row` in column `index`.
+    fn field(row: &[String], index: Option<usize>) -> Option<&str> {
+        index
+            .and_then(|index| row.get(index))
+            .map(|field| field.trim())
+            .filter(|field| !field.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DEFAULT_LIST_ID;
+
+    #[test]
+    fn dry_run_reports_created_lists_and_labels() {
+        let lists = vec![
+            ImportedList {
+                project: Some("Home".to_string()),
+                section: Some("Garden".to_string()),
+                todos: vec![ImportedTodo {
+                    title: "Mow".to_string(),
+                    label: Some("Weekend".to_string()),
+                    ..ImportedTodo::default()
+                }],
+            },
+            ImportedList {
+                project: None,
+                section: None,
+                todos: vec![ImportedTodo {
+                    title: "Inbox item".to_string(),
+                    ..ImportedTodo::default()
+                }],
+            },
+        ];
+        let mut data = AppData::default();
+        let report = apply(&mut data, lists, DEFAULT_LIST_ID).expect("default list exists");
+
+        assert_eq!(report.todos, 2);
+        assert_eq!(report.lists.len(), 2);
+        assert_eq!(report.lists[0].name, "Home / Garden");
+        assert!(report.lists[0].created);
+        assert!(!report.lists[1].created);
+        assert_eq!(report.labels_created, vec!["Weekend".to_string()]);
+    }
+
+    #[test]
+    fn reads_quoted_csv_fields() {
+        let records = parse_csv("\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n\n");
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["x, \"y\"".to_string(), "multi\nline".to_string()],
+            ]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Things 3 JSON, the format of its `add-json` URL command: an array of
+//! `to-do` and `project` items. A project holds to-dos and `heading`s, each
+//! heading grouping the to-dos after it. `when` is the start date, with an
+//! optional `@HH:MM` reminder; `deadline` becomes the reminder otherwise.
+//! Checklist items become subtasks.
+
+use chrono::Local;
+use serde::Deserialize;
+
+use super::{parse_timestamp, ImportedList};
+use crate::formats::ImportedTodo;
+use crate::storage::now_millis;
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    attributes: Attributes,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Attributes {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    when: Option<String>,
+    #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    canceled: bool,
+    #[serde(default)]
+    creation_date: Option<String>,
+    #[serde(default)]
+    completion_date: Option<String>,
+    /// Project or area of a top-level to-do.
+    #[serde(default)]
+    list: Option<String>,
+    #[serde(default)]
+    heading: Option<String>,
+    #[serde(default)]
+    checklist_items: Vec<Item>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+pub fn parse_json(content: &str, warnings: &mut Vec<String>) -> Result<Vec<ImportedList>, String> {
+    let items = serde_json::from_str::<Vec<Item>>(content)
+        .map_err(|error| format!("not a Things JSON export: {error}"))?;
+    let mut lists: Vec<ImportedList> = Vec::new();
+
+    for item in items {
+        match item.kind.as_str() {
+            "to-do" => {
+                let project = item.attributes.list.clone();
+                let section = item.attributes.heading.clone();
+                let todo = todo(item.attributes, warnings);
+                push(&mut lists, project, section, todo);
+            }
+            "project" => {
+                let project = Some(item.attributes.title.clone());
+                let mut heading = None;
+                for child in item.attributes.items {
+                    match child.kind.as_str() {
+                        "heading" => heading = Some(child.attributes.title),
+                        "to-do" => {
+                            let todo = todo(child.attributes, warnings);
+                            push(&mut lists, project.clone(), heading.clone(), todo);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            other => warnings.push(format!("a Things item of type \"{other}\" was skipped")),
+        }
+    }
+
+    Ok(lists)
+}
+
+/// Adds `todo` to the list of `project` and `section`, in order of first
+/// appearance.
+fn push(
+    lists: &mut Vec<ImportedList>,
+    project: Option<String>,
+    section: Option<String>,
+    todo: ImportedTodo,
+) {
+    match lists
+        .iter_mut()
+        .find(|list| list.project == project && list.section == section)
+    {
+        Some(list) => list.todos.push(todo),
+        None => lists.push(ImportedList {
+            project,
+            section,
+            todos: vec![todo],
+        }),
+    }
+}
+
+fn todo(attributes: Attributes, warnings: &mut Vec<String>) -> ImportedTodo {
+    let (start_at, when_reminder) = attributes
+        .when
+        .as_deref()
+        .map(|when| parse_when(when, &attributes.title, warnings))
+        .unwrap_or_default();
+    let deadline = attributes.deadline.as_deref().and_then(|deadline| {
+        let parsed = parse_timestamp(deadline);
+        if parsed.is_none() {
+            warnings.push(format!(
+                "the deadline \"{deadline}\" of \"{}\" was not understood",
+                attributes.title
+            ));
+        }
+        parsed
+    });
+    let done = attributes.completed || attributes.canceled;
+
+    ImportedTodo {
+        details: attributes.notes.filter(|notes| !notes.trim().is_empty()),
+        label: attributes
+            .tags
+            .into_iter()
+            .find(|tag| !tag.trim().is_empty()),
+        created_at: attributes
+            .creation_date
+            .as_deref()
+            .and_then(parse_timestamp),
+        completed_at: done.then(|| {
+            attributes
+                .completion_date
+                .as_deref()
+                .and_then(parse_timestamp)
+                .unwrap_or_else(now_millis)
+        }),
+        start_at,
+        reminder_at: when_reminder.or(deadline),
+        children: attributes
+            .checklist_items
+            .into_iter()
+            .filter(|item| item.kind == "checklist-item")
+            .map(|item| ImportedTodo {
+                title: item.attributes.title,
+                completed_at: (item.attributes.completed || item.attributes.canceled)
+                    .then(now_millis),
+                ..ImportedTodo::default()
+            })
+            .collect(),
+        title: attributes.title,
+        ..ImportedTodo::default()
+    }
+}
+
+/// The start date of `when` and its reminder: `today`, `tomorrow`,
+/// `evening`, a date, or a date with `@HH:MM`. `anytime` and `someday` have
+/// neither.
+fn parse_when(when: &str, title: &str, warnings: &mut Vec<String>) -> (Option<i64>, Option<i64>) {
+    let (day, time) = match when.split_once('@') {
+        Some((day, time)) => (day.trim(), Some(time.trim())),
+        None => (when.trim(), None),
+    };
+    let today = Local::now().date_naive();
+    let date = match day.to_lowercase().as_str() {
+        "anytime" | "someday" => return (None, None),
+        "today" | "evening" | "tonight" => today.format("%Y-%m-%d").to_string(),
+        "tomorrow" => today
+            .succ_opt()
+            .unwrap_or(today)
+            .format("%Y-%m-%d")
+            .to_string(),
+        _ => day.to_string(),
+    };
+
+    let Some(start_at) = parse_timestamp(&date) else {
+        warnings.push(format!(
+            "the date \"{when}\" of \"{title}\" was not understood"
+        ));
+        return (None, None);
+    };
+    let reminder_at = time.and_then(|time| parse_timestamp(&format!("{date}T{time}")));
+    (Some(start_at), reminder_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_projects_headings_and_checklists() {
+        let json = r#"[
+            {"type": "to-do", "attributes": {"title": "Loose", "when": "someday"}},
+            {"type": "project", "attributes": {"title": "Move", "items": [
+                {"type": "to-do", "attributes": {"title": "Find movers", "tags": ["Calls"],
+                 "when": "2026-05-01@10:30", "deadline": "2026-05-10"}},
+                {"type": "heading", "attributes": {"title": "Packing"}},
+                {"type": "to-do", "attributes": {"title": "Boxes", "completed": true,
+                 "checklist-items": [
+                    {"type": "checklist-item", "attributes": {"title": "Tape", "completed": true}}
+                 ]}}
+            ]}},
+            {"type": "area", "attributes": {"title": "Home"}}
+        ]"#;
+        let mut warnings = Vec::new();
+        let lists = parse_json(json, &mut warnings).expect("valid export");
+
+        assert_eq!(lists.len(), 3);
+        assert_eq!(lists[0].project, None);
+        assert_eq!(lists[0].todos[0].start_at, None);
+        let movers = &lists[1].todos[0];
+        assert_eq!(movers.label.as_deref(), Some("Calls"));
+        assert_eq!(movers.start_at, parse_timestamp("2026-05-01"));
+        assert_eq!(movers.reminder_at, parse_timestamp("2026-05-01T10:30"));
+        assert_eq!(lists[2].section.as_deref(), Some("Packing"));
+        assert!(lists[2].todos[0].completed_at.is_some());
+        assert!(lists[2].todos[0].children[0].completed_at.is_some());
+        assert_eq!(warnings.len(), 1);
+    }
+}
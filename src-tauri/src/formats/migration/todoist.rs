@@ -0,0 +1,424 @@
+//! Todoist. The CSV export holds one project: `task` rows nested by
+//! `INDENT`, `section` rows starting a section and `note` rows commenting
+//! the task above. Labels are `@name` words of the content. Priorities go
+//! from 1 (p1, urgent) to 4 (none) in CSV files, and the other way around in
+//! backups.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{parse_timestamp, CsvTable, ImportedList};
+use crate::formats::{attach, build_tree, ImportedTodo};
+use crate::storage::{now_millis, TodoPriority};
+
+pub fn parse_csv(content: &str, warnings: &mut Vec<String>) -> Result<Vec<ImportedList>, String> {
+    let table = CsvTable::parse(content)?;
+    let (Some(kind), Some(text)) = (table.column("type"), table.column("content")) else {
+        return Err("not a Todoist CSV export: TYPE and CONTENT columns are missing".to_string());
+    };
+    let description = table.column("description");
+    let priority = table.column("priority");
+    let indent = table.column("indent");
+    let date = table.column("date");
+
+    let mut lists = vec![ImportedList::default()];
+    let mut items: Vec<(usize, ImportedTodo)> = Vec::new();
+
+    for row in &table.rows {
+        let Some(text) = CsvTable::field(row, Some(text)) else {
+            continue;
+        };
+
+        match CsvTable::field(row, Some(kind))
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("task") => {
+                let (title, labels) = split_labels(text);
+                let mut todo = ImportedTodo {
+                    title,
+                    details: CsvTable::field(row, description).map(str::to_string),
+                    priority: CsvTable::field(row, priority)
+                        .and_then(|value| value.parse().ok())
+                        .map(csv_priority)
+                        .unwrap_or_default(),
+                    label: labels.into_iter().next(),
+                    ..ImportedTodo::default()
+                };
+                if let Some(date) = CsvTable::field(row, date) {
+                    todo.reminder_at = parse_due(date, &todo.title, warnings);
+                }
+
+                let depth = CsvTable::field(row, indent)
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .unwrap_or(1);
+                items.push((depth, todo));
+            }
+            Some("section") => {
+                flush(&mut lists, &mut items);
+                lists.push(ImportedList {
+                    section: Some(text.to_string()),
+                    ..ImportedList::default()
+                });
+            }
+            Some("note") => {
+                if let Some((_, todo)) = items.last_mut() {
+                    let details = todo.details.get_or_insert_with(String::new);
+                    if !details.is_empty() {
+                        details.push_str("\n\n");
+                    }
+                    details.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut lists, &mut items);
+
+    lists.retain(|list| !list.todos.is_empty());
+    Ok(lists)
+}
+
+fn flush(lists: &mut [ImportedList], items: &mut Vec<(usize, ImportedTodo)>) {
+    if let Some(list) = lists.last_mut() {
+        list.todos.extend(build_tree(std::mem::take(items)));
+    }
+}
+
+#[derive(Deserialize)]
+struct Backup {
+    #[serde(default)]
+    projects: Vec<Project>,
+    #[serde(default)]
+    sections: Vec<Section>,
+    #[serde(default)]
+    items: Vec<Item>,
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    id: Value,
+    name: String,
+    #[serde(default)]
+    inbox_project: bool,
+    #[serde(default)]
+    child_order: i64,
+}
+
+#[derive(Deserialize)]
+struct Section {
+    id: Value,
+    name: String,
+    #[serde(default)]
+    section_order: i64,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    id: Value,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    id: Value,
+    content: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    project_id: Value,
+    #[serde(default)]
+    section_id: Value,
+    #[serde(default)]
+    parent_id: Value,
+    #[serde(default = "default_priority")]
+    priority: u8,
+    #[serde(default)]
+    labels: Vec<Value>,
+    #[serde(default)]
+    child_order: i64,
+    /// `true`, or `1` in older backups.
+    #[serde(default)]
+    checked: Value,
+    #[serde(default)]
+    is_deleted: Value,
+    #[serde(default)]
+    due: Option<Due>,
+    #[serde(default, alias = "date_added")]
+    added_at: Option<String>,
+    #[serde(default, alias = "date_completed")]
+    completed_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Due {
+    date: String,
+    #[serde(default)]
+    is_recurring: bool,
+}
+
+type GroupKey = (Option<String>, Option<String>);
+
+fn default_priority() -> u8 {
+    1
+}
+
+pub fn parse_json(content: &str, warnings: &mut Vec<String>) -> Result<Vec<ImportedList>, String> {
+    let backup = serde_json::from_str::<Backup>(content)
+        .map_err(|error| format!("not a Todoist backup: {error}"))?;
+
+    let label_names = backup
+        .labels
+        .iter()
+        .filter_map(|label| Some((id(&label.id)?, label.name.as_str())))
+        .collect::<HashMap<_, _>>();
+    let projects = backup
+        .projects
+        .iter()
+        .filter_map(|project| Some((id(&project.id)?, project)))
+        .collect::<HashMap<_, _>>();
+    let sections = backup
+        .sections
+        .iter()
+        .filter_map(|section| Some((id(&section.id)?, section)))
+        .collect::<HashMap<_, _>>();
+
+    let mut items = backup
+        .items
+        .into_iter()
+        .filter(|item| !truthy(&item.is_deleted))
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| item.child_order);
+
+    // Todos grouped by project and section ids, with their parent in the group.
+    let mut groups: Vec<(GroupKey, Vec<Item>)> = Vec::new();
+    for item in items {
+        let key = (
+            id(&item.project_id).filter(|id| projects.contains_key(id)),
+            id(&item.section_id).filter(|id| sections.contains_key(id)),
+        );
+        match groups.iter_mut().find(|(group, _)| *group == key) {
+            Some((_, group_items)) => group_items.push(item),
+            None => groups.push((key, vec![item])),
+        }
+    }
+    groups.sort_by_key(|((project, section), _)| {
+        (
+            project
+                .as_ref()
+                .and_then(|id| projects.get(id))
+                .map_or(i64::MIN, |project| project.child_order),
+            section
+                .as_ref()
+                .and_then(|id| sections.get(id))
+                .map_or(i64::MIN, |section| section.section_order),
+        )
+    });
+
+    let mut lists = Vec::new();
+    for ((project, section), items) in groups {
+        let parents = items
+            .iter()
+            .map(|item| {
+                let parent_id = id(&item.parent_id)?;
+                items
+                    .iter()
+                    .position(|other| id(&other.id).as_ref() == Some(&parent_id))
+            })
+            .collect::<Vec<_>>();
+        let todos = items
+            .into_iter()
+            .map(|item| item_todo(item, &label_names, warnings))
+            .collect();
+
+        lists.push(ImportedList {
+            project: project
+                .and_then(|id| projects.get(&id).copied())
+                .filter(|project| !project.inbox_project)
+                .map(|project| project.name.clone()),
+            section: section
+                .and_then(|id| sections.get(&id).copied())
+                .map(|section| section.name.clone()),
+            todos: attach(todos, &parents),
+        });
+    }
+
+    Ok(lists)
+}
+
+fn item_todo(
+    item: Item,
+    label_names: &HashMap<String, &str>,
+    warnings: &mut Vec<String>,
+) -> ImportedTodo {
+    let (title, inline_labels) = split_labels(&item.content);
+    // Recent backups list label names, older ones label ids.
+    let label = item
+        .labels
+        .iter()
+        .filter_map(|label| match label {
+            Value::String(name) => Some(name.clone()),
+            other => id(other).and_then(|id| label_names.get(&id).map(|name| name.to_string())),
+        })
+        .chain(inline_labels)
+        .next();
+
+    let mut todo = ImportedTodo {
+        title,
+        details: item
+            .description
+            .filter(|details| !details.trim().is_empty()),
+        priority: backup_priority(item.priority),
+        label,
+        created_at: item.added_at.as_deref().and_then(parse_timestamp),
+        ..ImportedTodo::default()
+    };
+    if truthy(&item.checked) {
+        todo.completed_at = item
+            .completed_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or(Some(now_millis()));
+    }
+    if let Some(due) = item.due {
+        if due.is_recurring {
+            warnings.push(format!(
+                "\"{}\" repeats in Todoist; only its next date was imported",
+                todo.title
+            ));
+        }
+        todo.reminder_at = parse_due(&due.date, &todo.title, warnings);
+    }
+    todo
+}
+
+/// The content without its `@label` words, and the labels.
+fn split_labels(content: &str) -> (String, Vec<String>) {
+    let mut labels = Vec::new();
+    let title = content
+        .split_whitespace()
+        .filter(|word| match word.strip_prefix('@') {
+            Some(label) if !label.is_empty() => {
+                labels.push(label.to_string());
+                false
+            }
+            _ => true,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    (title, labels)
+}
+
+fn parse_due(date: &str, title: &str, warnings: &mut Vec<String>) -> Option<i64> {
+    let parsed = parse_timestamp(date);
+    if parsed.is_none() {
+        warnings.push(format!(
+            "the due date \"{date}\" of \"{title}\" was not understood"
+        ));
+    }
+    parsed
+}
+
+fn csv_priority(value: u8) -> TodoPriority {
+    match value {
+        1 => TodoPriority::Urgent,
+        2 => TodoPriority::High,
+        3 => TodoPriority::Medium,
+        _ => TodoPriority::None,
+    }
+}
+
+fn backup_priority(value: u8) -> TodoPriority {
+    match value {
+        4 => TodoPriority::Urgent,
+        3 => TodoPriority::High,
+        2 => TodoPriority::Medium,
+        _ => TodoPriority::None,
+    }
+}
+
+/// Ids are strings in recent backups and numbers in older ones.
+fn id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(value) => *value,
+        Value::Number(value) => value.as_i64().is_some_and(|value| value != 0),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_csv_sections_indents_and_notes() {
+        let csv = "\
+TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE
+task,Plan the trip @travel,,1,1,,,2026-04-01,en,Europe/Paris
+task,Book flights,,4,2,,,,en,Europe/Paris
+note,Prefer morning flights,,,,,,,,
+section,Packing,,,,,,,,
+task,Buy adapters,,2,1,,,every monday,en,Europe/Paris
+";
+        let mut warnings = Vec::new();
+        let lists = parse_csv(csv, &mut warnings).expect("valid export");
+
+        assert_eq!(lists.len(), 2);
+        let plan = &lists[0].todos[0];
+        assert_eq!(plan.title, "Plan the trip");
+        assert_eq!(plan.label.as_deref(), Some("travel"));
+        assert_eq!(plan.priority, TodoPriority::Urgent);
+        assert!(plan.reminder_at.is_some());
+        assert_eq!(
+            plan.children[0].details.as_deref(),
+            Some("Prefer morning flights")
+        );
+        assert_eq!(lists[1].section.as_deref(), Some("Packing"));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn reads_backup_projects_and_subtasks() {
+        let json = r#"{
+            "projects": [
+                {"id": "1", "name": "Inbox", "inbox_project": true, "child_order": 0},
+                {"id": "2", "name": "Work", "child_order": 1}
+            ],
+            "sections": [{"id": "10", "name": "Review", "section_order": 1}],
+            "labels": [{"id": 7, "name": "deep"}],
+            "items": [
+                {"id": "101", "content": "Sub step", "project_id": "2", "section_id": "10",
+                 "parent_id": "100", "child_order": 2, "priority": 1},
+                {"id": "100", "content": "Review PR", "project_id": "2", "section_id": "10",
+                 "child_order": 1, "priority": 4, "labels": [7], "checked": 1,
+                 "due": {"date": "2026-03-05T14:00:00", "is_recurring": false}},
+                {"id": "102", "content": "Call mom", "project_id": "1", "child_order": 1}
+            ]
+        }"#;
+        let mut warnings = Vec::new();
+        let lists = parse_json(json, &mut warnings).expect("valid backup");
+
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0].project, None);
+        assert_eq!(lists[1].project.as_deref(), Some("Work"));
+        assert_eq!(lists[1].section.as_deref(), Some("Review"));
+        let review = &lists[1].todos[0];
+        assert_eq!(review.priority, TodoPriority::Urgent);
+        assert_eq!(review.label.as_deref(), Some("deep"));
+        assert!(review.completed_at.is_some());
+        assert_eq!(review.children[0].title, "Sub step");
+        assert!(warnings.is_empty());
+    }
+}
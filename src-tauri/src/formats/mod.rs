@@ -0,0 +1,420 @@
+//! Conversions between `AppData` and the file formats of other tools. Each
+//! importing submodule parses into `ImportedTodo` trees, which `insert_tree`
+//! adds to a list with the same normalization as todos created in the app.
+
+pub mod ical;
+pub mod markdown;
+pub mod migration;
+pub mod opml;
+pub mod paste;
+pub mod report;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use std::collections::HashMap;
+
+use chrono::{Local, TimeZone};
+use uuid::Uuid;
+
+use crate::storage::{now_millis, AppData, Todo, TodoLabel, TodoList, TodoPriority};
+
+/// Deepest subtask level read or written, so that recursion over the trees
+/// stays bounded whatever the file.
+const MAX_DEPTH: usize = 64;
+
+/// A todo read from another format, with its subtasks in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedTodo {
+    /// Id to keep, used when no todo has it yet.
+    pub id: Option<String>,
+    pub title: String,
+    pub details: Option<String>,
+    pub priority: TodoPriority,
+    /// Label name, matched case-insensitively and created when missing.
+    pub label: Option<String>,
+    pub starred: bool,
+    pub created_at: Option<i64>,
+    pub completed_at: Option<i64>,
+    pub reminder_at: Option<i64>,
+    pub start_at: Option<i64>,
+    pub children: Vec<ImportedTodo>,
+}
+
+/// Adds `todos` and their subtasks to `list_id`, after the todos already in
+/// it, and returns how many todos were created. Blank titles are skipped
+/// together with their subtasks.
+pub fn insert_tree(
+    data: &mut AppData,
+    list_id: &str,
+    todos: Vec<ImportedTodo>,
+) -> Result<usize, String> {
+    insert_tree_under(data, list_id, None, todos)
+}
+
+/// Like `insert_tree`, with the roots of `todos` added as the last subtasks
+/// of `parent_id`, which must belong to `list_id`.
+pub fn insert_tree_under(
+    data: &mut AppData,
+    list_id: &str,
+    parent_id: Option<&str>,
+    todos: Vec<ImportedTodo>,
+) -> Result<usize, String> {
+    if !data.settings.lists.iter().any(|list| list.id == list_id) {
+        return Err(format!("unknown list {list_id}"));
+    }
+    if let Some(parent_id) = parent_id {
+        if !data
+            .todos
+            .iter()
+            .any(|todo| todo.id == parent_id && todo.list_id.as_deref() == Some(list_id))
+        {
+            return Err(format!("unknown todo {parent_id} in list {list_id}"));
+        }
+    }
+
+    let first_sort_index = data
+        .todos
+        .iter()
+        .filter(|todo| {
+            todo.list_id.as_deref() == Some(list_id) && todo.parent_id.as_deref() == parent_id
+        })
+        .filter_map(|todo| todo.sort_index)
+        .max()
+        .map_or(0, |value| value.saturating_add(1));
+
+    let mut inserted = 0;
+    insert_level(
+        data,
+        list_id,
+        parent_id,
+        first_sort_index,
+        todos,
+        now_millis(),
+        &mut inserted,
+    );
+    Ok(inserted)
+}
+
+fn insert_level(
+    data: &mut AppData,
+    list_id: &str,
+    parent_id: Option<&str>,
+    first_sort_index: i64,
+    todos: Vec<ImportedTodo>,
+    now: i64,
+    inserted: &mut usize,
+) {
+    let mut sort_index = first_sort_index;
+
+    for todo in todos {
+        let title = todo.title.trim();
+        if title.is_empty() {
+            continue;
+        }
+
+        let id = todo
+            .id
+            .filter(|id| !id.is_empty() && !data.todos.iter().any(|other| other.id == *id))
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let label_id = todo
+            .label
+            .as_deref()
+            .and_then(|name| resolve_label(data, name));
+
+        data.todos.push(Todo {
+            id: id.clone(),
+            title: title.to_string(),
+            details: todo
+                .details
+                .map(|details| details.trim_end().to_string())
+                .filter(|details| !details.trim().is_empty()),
+            parent_id: parent_id.map(str::to_string),
+            list_id: Some(list_id.to_string()),
+            starred: todo.starred,
+            priority: todo.priority,
+            label_id,
+            sort_index: Some(sort_index),
+            created_at: todo.created_at.unwrap_or(now),
+            completed_at: todo.completed_at,
+            reminder_at: todo.reminder_at,
+            start_at: todo.start_at,
+            updated_at: None,
+        });
+        *inserted += 1;
+        sort_index += 1;
+
+        insert_level(data, list_id, Some(&id), 0, todo.children, now, inserted);
+    }
+}
+
+/// Adds `todos`, read from a flat file, to the list at the same index of
+/// `targets`, nested under the todo at `parents[index]` when it went to the
+/// same list. Lists are filled in order of first appearance. Returns how many
+/// todos were created.
+fn insert_by_list(
+    data: &mut AppData,
+    todos: Vec<ImportedTodo>,
+    targets: &[String],
+    parents: &[Option<usize>],
+) -> Result<usize, String> {
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (index, target) in targets.iter().enumerate() {
+        match groups.iter_mut().find(|(list_id, _)| *list_id == target) {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((target, vec![index])),
+        }
+    }
+
+    let mut todos = todos.into_iter().map(Some).collect::<Vec<_>>();
+    let mut count = 0;
+
+    for (target, indices) in groups {
+        let positions = indices
+            .iter()
+            .enumerate()
+            .map(|(position, index)| (*index, position))
+            .collect::<HashMap<_, _>>();
+        let group_parents = indices
+            .iter()
+            .map(|index| {
+                parents
+                    .get(*index)
+                    .copied()
+                    .flatten()
+                    .and_then(|parent| positions.get(&parent).copied())
+            })
+            .collect::<Vec<_>>();
+        let group_todos = indices
+            .iter()
+            .filter_map(|index| todos.get_mut(*index).and_then(Option::take))
+            .collect();
+
+        count += insert_tree(data, target, attach(group_todos, &group_parents))?;
+    }
+
+    Ok(count)
+}
+
+/// Id of the label named `name`, creating it when there is none.
+fn resolve_label(data: &mut AppData, name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(label) = data
+        .settings
+        .labels
+        .iter()
+        .find(|label| label.name.to_lowercase() == name.to_lowercase())
+    {
+        return Some(label.id.clone());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    data.settings.labels.push(TodoLabel {
+        id: id.clone(),
+        name: name.to_string(),
+        color: "slate".to_string(),
+    });
+    Some(id)
+}
+
+/// Id of the list named `name`, creating it when there is none.
+fn resolve_list(data: &mut AppData, name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(list) = data
+        .settings
+        .lists
+        .iter()
+        .find(|list| list.name.to_lowercase() == name.to_lowercase())
+    {
+        return Some(list.id.clone());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    data.settings.lists.push(TodoList {
+        id: id.clone(),
+        name: name.to_string(),
+        icon: None,
+        notification_sound: None,
+        created_at: now_millis(),
+    });
+    Some(id)
+}
+
+/// Builds trees from items listed in document order with their indentation:
+/// an item becomes a subtask of the closest previous item indented less.
+pub fn build_tree(items: Vec<(usize, ImportedTodo)>) -> Vec<ImportedTodo> {
+    let mut parents: Vec<Option<usize>> = Vec::with_capacity(items.len());
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for (index, (indent, _)) in items.iter().enumerate() {
+        while stack.last().is_some_and(|(open, _)| *open >= *indent) {
+            stack.pop();
+        }
+        parents.push(stack.last().map(|(_, parent)| *parent));
+        stack.push((*indent, index));
+    }
+
+    attach(items.into_iter().map(|(_, todo)| todo).collect(), &parents)
+}
+
+/// Nests every todo under the one at `parents[index]`, whatever their order,
+/// at most `MAX_DEPTH` levels deep. Other todos stay at the root, in their
+/// original order; a parent cycle is cut above its first todo.
+fn attach(todos: Vec<ImportedTodo>, parents: &[Option<usize>]) -> Vec<ImportedTodo> {
+    let count = todos.len();
+    let mut children = vec![Vec::new(); count];
+    let mut roots = Vec::new();
+
+    for index in 0..count {
+        match parents
+            .get(index)
+            .copied()
+            .flatten()
+            .filter(|parent| *parent < count && *parent != index)
+        {
+            Some(parent) => children[parent].push(index),
+            None => roots.push(index),
+        }
+    }
+
+    // Walks down from the roots, then from the todos of a cycle, which
+    // cannot be reached from a root. Todos deeper than `MAX_DEPTH` become
+    // subtasks of their ancestor one level up.
+    let mut nested = vec![Vec::new(); count];
+    let mut reached = vec![false; count];
+    let walk = |root: usize, reached: &mut [bool], nested: &mut [Vec<usize>]| {
+        reached[root] = true;
+        let mut pending = vec![(root, None, 0)];
+        while let Some((index, parent, depth)) = pending.pop() {
+            for &child in &children[index] {
+                if std::mem::replace(&mut reached[child], true) {
+                    continue;
+                }
+                let (parent, depth) = match parent {
+                    Some(parent) if depth >= MAX_DEPTH => (parent, depth),
+                    _ => (index, depth + 1),
+                };
+                nested[parent].push(child);
+                pending.push((child, Some(parent), depth));
+            }
+        }
+    };
+    for root in &roots {
+        walk(*root, &mut reached, &mut nested);
+    }
+    for index in 0..count {
+        if !reached[index] {
+            roots.push(index);
+            walk(index, &mut reached, &mut nested);
+        }
+    }
+    roots.sort_unstable();
+    for siblings in &mut nested {
+        siblings.sort_unstable();
+    }
+
+    fn take(
+        index: usize,
+        nodes: &mut [Option<ImportedTodo>],
+        children: &[Vec<usize>],
+    ) -> Option<ImportedTodo> {
+        let mut todo = nodes[index].take()?;
+        todo.children = children[index]
+            .iter()
+            .filter_map(|child| take(*child, nodes, children))
+            .collect();
+        Some(todo)
+    }
+
+    let mut nodes = todos.into_iter().map(Some).collect::<Vec<_>>();
+    roots
+        .into_iter()
+        .filter_map(|root| take(root, &mut nodes, &nested))
+        .collect()
+}
+
+/// The lists to export: the one with `list_id`, or every list when it is
+/// `None`.
+pub fn export_lists<'a>(
+    data: &'a AppData,
+    list_id: Option<&str>,
+) -> Result<Vec<&'a TodoList>, String> {
+    let lists = data
+        .settings
+        .lists
+        .iter()
+        .filter(|list| list_id.map_or(true, |list_id| list.id == list_id))
+        .collect::<Vec<_>>();
+
+    if lists.is_empty() {
+        return Err(format!("unknown list {}", list_id.unwrap_or_default()));
+    }
+    Ok(lists)
+}
+
+/// Todos of `list_id` as trees, siblings in display order.
+pub fn list_trees<'a>(data: &'a AppData, list_id: &str) -> Vec<TodoTree<'a>> {
+    let in_list = data
+        .todos
+        .iter()
+        .filter(|todo| todo.list_id.as_deref() == Some(list_id))
+        .collect::<Vec<_>>();
+
+    let mut children: HashMap<Option<&str>, Vec<&Todo>> = HashMap::new();
+    for todo in &in_list {
+        let parent = todo
+            .parent_id
+            .as_deref()
+            .filter(|parent_id| in_list.iter().any(|todo| todo.id == *parent_id));
+        children.entry(parent).or_default().push(todo);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|todo| (todo.sort_index.unwrap_or(i64::MAX), todo.created_at));
+    }
+
+    fn collect<'a>(
+        children: &HashMap<Option<&'a str>, Vec<&'a Todo>>,
+        parent: Option<&'a str>,
+        depth: usize,
+    ) -> Vec<TodoTree<'a>> {
+        // Guards against parent cycles in hand-edited files.
+        if depth > MAX_DEPTH {
+            return Vec::new();
+        }
+
+        children
+            .get(&parent)
+            .map(|todos| {
+                todos
+                    .iter()
+                    .map(|todo| TodoTree {
+                        todo,
+                        children: collect(children, Some(todo.id.as_str()), depth + 1),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    collect(&children, None, 0)
+}
+
+pub struct TodoTree<'a> {
+    pub todo: &'a Todo,
+    pub children: Vec<TodoTree<'a>>,
+}
+
+/// Local `YYYY-MM-DDTHH:MM`, readable by `storage::parse_date_time`.
+pub fn format_local(millis: i64) -> Option<String> {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|date_time| date_time.format("%Y-%m-%dT%H:%M").to_string())
+}
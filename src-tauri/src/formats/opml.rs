@@ -0,0 +1,314 @@
+//! OPML 2.0 outlines, for outliners: a list is the document and each todo an
+//! `<outline>` nested under its parent.
+//!
+//! ```xml
+//! <outline text="Prepare the release" priority="high" label="Deep work">
+//!   <outline text="Update the changelog" _complete="true"/>
+//! </outline>
+//! ```
+//!
+//! - `text` is the title and `_note` the details, as in most outliners.
+//! - `_complete="true"` marks completed todos; Dynalist's `checked` and
+//!   OmniOutliner's `_status="checked"` are read too.
+//! - `priority` is `low` … `urgent`, `label` a label name, and `reminder` and
+//!   `start` local date-times.
+//!
+//! Other elements and attributes are ignored on import.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use tauri::{AppHandle, State};
+
+use super::markdown::{parse_priority, priority_token};
+use super::{build_tree, format_local, insert_tree, list_trees, ImportedTodo, TodoTree};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, parse_date_time, AppData, AppState};
+
+const INDENT: &str = "  ";
+
+#[tauri::command]
+pub fn export_opml(list_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+    to_opml(&data, &list_id)
+}
+
+/// Adds the outlines of `content` to `list_id`, keeping their nesting and
+/// order.
+#[tauri::command]
+pub fn import_opml(
+    content: String,
+    list_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let todos = parse(&content)?;
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let count = insert_tree(&mut guard, &list_id, todos)?;
+        log::info!("imported {count} todos from OPML into {list_id}");
+    }
+
+    persist_state(&app, &state)
+}
+
+pub fn to_opml(data: &AppData, list_id: &str) -> Result<String, String> {
+    let list = data
+        .settings
+        .lists
+        .iter()
+        .find(|list| list.id == list_id)
+        .ok_or_else(|| format!("unknown list {list_id}"))?;
+
+    let mut output = String::new();
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<opml version=\"2.0\">\n");
+    output.push_str(&format!(
+        "{INDENT}<head>\n{INDENT}{INDENT}<title>{}</title>\n{INDENT}</head>\n",
+        escape(&list.name)
+    ));
+    output.push_str(&format!("{INDENT}<body>\n"));
+    for tree in list_trees(data, &list.id) {
+        write_tree(&mut output, data, &tree, 2);
+    }
+    output.push_str(&format!("{INDENT}</body>\n"));
+    output.push_str("</opml>\n");
+
+    Ok(output)
+}
+
+fn write_tree(output: &mut String, data: &AppData, tree: &TodoTree, depth: usize) {
+    let todo = tree.todo;
+    let mut attributes = vec![("text", todo.title.clone())];
+
+    if let Some(details) = &todo.details {
+        attributes.push(("_note", details.clone()));
+    }
+    if todo.completed_at.is_some() {
+        attributes.push(("_complete", "true".to_string()));
+    }
+    if let Some(token) = priority_token(todo.priority) {
+        attributes.push(("priority", token.to_string()));
+    }
+    if let Some(label) = todo
+        .label_id
+        .as_deref()
+        .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+    {
+        attributes.push(("label", label.name.clone()));
+    }
+    if let Some(reminder) = todo.reminder_at.and_then(format_local) {
+        attributes.push(("reminder", reminder));
+    }
+    if let Some(start) = todo.start_at.and_then(format_local) {
+        attributes.push(("start", start));
+    }
+
+    let indent = INDENT.repeat(depth);
+    output.push_str(&format!("{indent}<outline"));
+    for (name, value) in attributes {
+        output.push_str(&format!(" {name}=\"{}\"", escape(&value)));
+    }
+
+    if tree.children.is_empty() {
+        output.push_str("/>\n");
+        return;
+    }
+    output.push_str(">\n");
+    for child in &tree.children {
+        write_tree(output, data, child, depth + 1);
+    }
+    output.push_str(&format!("{indent}</outline>\n"));
+}
+
+/// Escapes text and attribute values. Line breaks are written as character
+/// references, as XML parsers turn literal ones in attributes into spaces.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// The outlines of the document's `<body>` as todo trees.
+pub fn parse(content: &str) -> Result<Vec<ImportedTodo>, String> {
+    let now = now_millis();
+    let mut reader = Reader::from_str(content);
+    let mut items: Vec<(usize, ImportedTodo)> = Vec::new();
+    let mut found_opml = false;
+    let mut in_body = false;
+    // Whether each open outline was kept: one without text is dropped and its
+    // children move up a level.
+    let mut open = Vec::new();
+    let mut depth = 0;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|error| format!("not an OPML file: {error}"))?;
+        match event {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"opml" => found_opml = true,
+                b"body" => in_body = true,
+                b"outline" if in_body => {
+                    let todo = outline_todo(&element, now)?;
+                    let kept = !todo.title.is_empty();
+                    if kept {
+                        items.push((depth, todo));
+                        depth += 1;
+                    }
+                    open.push(kept);
+                }
+                _ => {}
+            },
+            Event::Empty(element) if in_body && element.local_name().as_ref() == b"outline" => {
+                let todo = outline_todo(&element, now)?;
+                if !todo.title.is_empty() {
+                    items.push((depth, todo));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"body" => in_body = false,
+                b"outline" if in_body => depth -= usize::from(open.pop() == Some(true)),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !found_opml {
+        return Err("not an OPML file: no <opml> element".to_string());
+    }
+    Ok(build_tree(items))
+}
+
+fn outline_todo(element: &BytesStart, now: i64) -> Result<ImportedTodo, String> {
+    let mut todo = ImportedTodo::default();
+    let mut completed = false;
+
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|error| format!("invalid outline attribute: {error}"))?;
+        let value = attribute
+            .unescape_value()
+            .map_err(|error| format!("invalid outline attribute: {error}"))?;
+        let value = value.trim();
+
+        match attribute.key.local_name().as_ref() {
+            b"text" => todo.title = value.to_string(),
+            // Some outliners write `title` without `text`.
+            b"title" if todo.title.is_empty() => todo.title = value.to_string(),
+            b"_note" => todo.details = Some(value.to_string()).filter(|note| !note.is_empty()),
+            b"_complete" | b"complete" | b"checked" => {
+                completed = value.eq_ignore_ascii_case("true");
+            }
+            b"_status" => completed = value.eq_ignore_ascii_case("checked"),
+            b"priority" => todo.priority = parse_priority(value).unwrap_or_default(),
+            b"label" => todo.label = Some(value.to_string()).filter(|label| !label.is_empty()),
+            b"reminder" => todo.reminder_at = parse_date_time(value),
+            b"start" => todo.start_at = parse_date_time(value),
+            _ => {}
+        }
+    }
+
+    if completed {
+        todo.completed_at = Some(now);
+    }
+    Ok(todo)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::storage::{Todo, TodoPriority, DEFAULT_LIST_ID};
+
+    #[test]
+    fn round_trips_nested_outlines() {
+        let source = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<opml version=\"2.0\">
+  <head>
+    <title>My tasks</title>
+  </head>
+  <body>
+    <outline text=\"Prepare the release\" _note=\"Check CI&#10;Then tag\" priority=\"high\" label=\"Deep work\" reminder=\"2026-03-02T09:00\">
+      <outline text=\"Update the changelog\" _complete=\"true\"/>
+      <outline text=\"Tag &quot;v1&quot; &amp; ship\">
+        <outline text=\"Sign the build\"/>
+      </outline>
+    </outline>
+    <outline text=\"Call the bank\"/>
+  </body>
+</opml>
+";
+        let mut data = AppData::default();
+        let todos = parse(source).expect("valid OPML");
+        let count = insert_tree(&mut data, DEFAULT_LIST_ID, todos).expect("list exists");
+        assert_eq!(count, 5);
+
+        let release = &data.todos[0];
+        assert_eq!(release.priority, TodoPriority::High);
+        assert_eq!(release.details.as_deref(), Some("Check CI\nThen tag"));
+        let tag = &data.todos[2];
+        assert_eq!(tag.title, "Tag \"v1\" & ship");
+        assert_eq!(tag.parent_id.as_deref(), Some(release.id.as_str()));
+        assert_eq!(tag.sort_index, Some(1));
+        assert_eq!(data.todos[3].parent_id.as_deref(), Some(tag.id.as_str()));
+        assert_eq!(data.todos[3].sort_index, Some(0));
+        assert!(data.todos[1].completed_at.is_some());
+
+        assert_eq!(
+            to_opml(&data, DEFAULT_LIST_ID).expect("list exists"),
+            source
+        );
+    }
+
+    #[test]
+    fn keeps_children_of_untitled_and_deeply_nested_outlines() {
+        let levels = 5_000;
+        let source = format!(
+            "<opml><body><outline text=\"\"><outline text=\"Orphan\"/></outline>{}{}</body></opml>",
+            "<outline text=\"Level\">".repeat(levels),
+            "</outline>".repeat(levels)
+        );
+
+        let todos = parse(&source).expect("valid OPML");
+        assert_eq!(todos[0].title, "Orphan");
+        let mut data = AppData::default();
+        let count = insert_tree(&mut data, DEFAULT_LIST_ID, todos).expect("list exists");
+        assert_eq!(count, levels + 1);
+
+        let parents = data
+            .todos
+            .iter()
+            .map(|todo| (todo.id.as_str(), todo.parent_id.as_deref()))
+            .collect::<HashMap<_, _>>();
+        let depth = |todo: &Todo| {
+            let mut depth = 0;
+            let mut parent_id = todo.parent_id.as_deref();
+            while let Some(id) = parent_id {
+                parent_id = parents[id];
+                depth += 1;
+            }
+            depth
+        };
+        assert_eq!(data.todos.iter().map(depth).max(), Some(64));
+    }
+
+    #[test]
+    fn rejects_other_xml() {
+        assert!(parse("<rss><channel/></rss>").is_err());
+        assert!(parse("<opml><body><outline text=\"a\"></body></opml>").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Blocks of text pasted into the quick-add, one todo per line:
+//!
+//! ```text
+//! Plan the trip
+//!     - [x] Book the flights
+//!     - [ ] Find a hotel
+//! 1. Renew the passport
+//! ```
+//!
+//! A line indented more than the previous one is its subtask. Bullets
+//! (`-`, `*`, `+`), numbers (`1.`, `1)`) and checkboxes are removed, `[x]`
+//! marking the todo completed. Titles are kept as written.
+
+use tauri::{AppHandle, State};
+
+use super::markdown::parse_item;
+use super::{build_tree, insert_tree_under, ImportedTodo};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, AppData, AppState};
+
+/// Adds the todos of `text` to `list_id`, or to the list of `parent_id`, or
+/// to the active list, under `parent_id` when set. All of them are saved at
+/// once, or none when the target is unknown.
+#[tauri::command]
+pub fn paste_todos(
+    text: String,
+    list_id: Option<String>,
+    parent_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    let todos = parse(&text);
+    if todos.is_empty() {
+        return persist_state(&app, &state);
+    }
+
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let parent_id = parent_id.filter(|id| !id.trim().is_empty());
+        let list_id = list_id
+            .filter(|id| !id.trim().is_empty())
+            .or_else(|| {
+                parent_id.as_deref().and_then(|parent_id| {
+                    guard
+                        .todos
+                        .iter()
+                        .find(|todo| todo.id == parent_id)
+                        .and_then(|todo| todo.list_id.clone())
+                })
+            })
+            .unwrap_or_else(|| guard.settings.active_list_id.clone());
+
+        let count = insert_tree_under(&mut guard, &list_id, parent_id.as_deref(), todos)?;
+        log::info!("pasted {count} todos into {list_id}");
+    }
+
+    persist_state(&app, &state)
+}
+
+pub fn parse(text: &str) -> Vec<ImportedTodo> {
+    let now = now_millis();
+    let mut items = Vec::new();
+
+    for raw_line in text.lines() {
+        let line = raw_line.replace('\t', "    ");
+        let indent = line.len() - line.trim_start().len();
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let (checked, title) = parse_item(text).unwrap_or_else(|| checkbox(text));
+        let title = title.trim();
+        if title.is_empty() {
+            continue;
+        }
+
+        items.push((
+            indent,
+            ImportedTodo {
+                title: title.to_string(),
+                completed_at: checked.then_some(now),
+                ..ImportedTodo::default()
+            },
+        ));
+    }
+
+    build_tree(items)
+}
+
+/// A checkbox without a bullet, as some editors copy them.
+fn checkbox(text: &str) -> (bool, &str) {
+    if let Some(title) = text.strip_prefix("[ ]") {
+        (false, title)
+    } else if let Some(title) = text
+        .strip_prefix("[x]")
+        .or_else(|| text.strip_prefix("[X]"))
+    {
+        (true, title)
+    } else {
+        (false, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::insert_todo;
+    use crate::storage::DEFAULT_LIST_ID;
+
+    #[test]
+    fn nests_pasted_lines_under_a_parent() {
+        let mut data = AppData::default();
+        let parent_id = insert_todo(
+            &mut data,
+            "Trip".to_string(),
+            None,
+            None,
+            None,
+            Some(DEFAULT_LIST_ID.to_string()),
+        )
+        .expect("created");
+        let text = "\
+Plan the route
+\t- [x] Book the flights
+\t* Find a hotel
+
+[ ] Renew the passport
+- [ ]
+";
+        let count = insert_tree_under(&mut data, DEFAULT_LIST_ID, Some(&parent_id), parse(text))
+            .expect("parent exists");
+        assert_eq!(count, 4);
+
+        let route = &data.todos[1];
+        assert_eq!(route.title, "Plan the route");
+        assert_eq!(route.parent_id.as_deref(), Some(parent_id.as_str()));
+        assert_eq!(route.sort_index, Some(0));
+        let flights = &data.todos[2];
+        assert_eq!(flights.parent_id.as_deref(), Some(route.id.as_str()));
+        assert!(flights.completed_at.is_some());
+        assert_eq!(data.todos[3].sort_index, Some(1));
+        assert_eq!(data.todos[4].title, "Renew the passport");
+        assert_eq!(data.todos[4].parent_id.as_deref(), Some(parent_id.as_str()));
+        assert_eq!(data.todos[4].sort_index, Some(1));
+
+        assert!(
+            insert_tree_under(&mut data, DEFAULT_LIST_ID, Some("missing"), parse("x")).is_err()
+        );
+    }
+}
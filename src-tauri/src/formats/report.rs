@@ -0,0 +1,366 @@
+//! Tabular exports for reporting: the todos matching a filter, one row each
+//! in display order, as CSV (RFC 4180) or a JSON array of objects. Only the
+//! selected columns are written, timestamps as ISO 8601 in local time. CSV
+//! cells that a spreadsheet would read as a formula start with `'`.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{Local, SecondsFormat, TimeZone};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tauri::State;
+
+use crate::commands::lock_error;
+use crate::storage::{ordered_todos, AppData, AppState, Todo};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// Absolute path of the file to write, replaced if it exists.
+    pub path: String,
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub filter: TodoFilter,
+    /// Every column when empty.
+    #[serde(default)]
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Todos kept by an export. Unset fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoFilter {
+    pub list_id: Option<String>,
+    pub label_id: Option<String>,
+    pub completed: Option<bool>,
+    /// Timestamp compared with `from` (inclusive) and `to` (exclusive).
+    #[serde(default)]
+    pub date_field: DateField,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateField {
+    #[default]
+    Created,
+    Completed,
+    Reminder,
+    Start,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Column {
+    Id,
+    Title,
+    Details,
+    List,
+    Label,
+    Priority,
+    Starred,
+    ParentId,
+    CreatedAt,
+    CompletedAt,
+    ReminderAt,
+    StartAt,
+}
+
+impl Column {
+    pub const ALL: [Column; 12] = [
+        Column::Id,
+        Column::Title,
+        Column::Details,
+        Column::List,
+        Column::Label,
+        Column::Priority,
+        Column::Starred,
+        Column::ParentId,
+        Column::CreatedAt,
+        Column::CompletedAt,
+        Column::ReminderAt,
+        Column::StartAt,
+    ];
+
+    /// Header in CSV files and key in JSON objects.
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Title => "title",
+            Column::Details => "details",
+            Column::List => "list",
+            Column::Label => "label",
+            Column::Priority => "priority",
+            Column::Starred => "starred",
+            Column::ParentId => "parentId",
+            Column::CreatedAt => "createdAt",
+            Column::CompletedAt => "completedAt",
+            Column::ReminderAt => "reminderAt",
+            Column::StartAt => "startAt",
+        }
+    }
+
+    fn value(self, data: &AppData, todo: &Todo) -> Value {
+        match self {
+            Column::Id => Value::from(todo.id.as_str()),
+            Column::Title => Value::from(todo.title.as_str()),
+            Column::Details => todo.details.as_deref().map_or(Value::Null, Value::from),
+            Column::List => data
+                .settings
+                .lists
+                .iter()
+                .find(|list| todo.list_id.as_deref() == Some(list.id.as_str()))
+                .map_or(Value::Null, |list| Value::from(list.name.as_str())),
+            Column::Label => data
+                .settings
+                .labels
+                .iter()
+                .find(|label| todo.label_id.as_deref() == Some(label.id.as_str()))
+                .map_or(Value::Null, |label| Value::from(label.name.as_str())),
+            Column::Priority => serde_json::to_value(todo.priority).unwrap_or(Value::Null),
+            Column::Starred => Value::from(todo.starred),
+            Column::ParentId => todo.parent_id.as_deref().map_or(Value::Null, Value::from),
+            Column::CreatedAt => iso_value(Some(todo.created_at)),
+            Column::CompletedAt => iso_value(todo.completed_at),
+            Column::ReminderAt => iso_value(todo.reminder_at),
+            Column::StartAt => iso_value(todo.start_at),
+        }
+    }
+}
+
+impl TodoFilter {
+    fn matches(&self, todo: &Todo) -> bool {
+        if self
+            .list_id
+            .as_deref()
+            .is_some_and(|list_id| todo.list_id.as_deref() != Some(list_id))
+        {
+            return false;
+        }
+        if self
+            .label_id
+            .as_deref()
+            .is_some_and(|label_id| todo.label_id.as_deref() != Some(label_id))
+        {
+            return false;
+        }
+        if self
+            .completed
+            .is_some_and(|completed| completed != todo.completed_at.is_some())
+        {
+            return false;
+        }
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+
+        let date = match self.date_field {
+            DateField::Created => Some(todo.created_at),
+            DateField::Completed => todo.completed_at,
+            DateField::Reminder => todo.reminder_at,
+            DateField::Start => todo.start_at,
+        };
+        date.is_some_and(|date| {
+            self.from.map_or(true, |from| date >= from) && self.to.map_or(true, |to| date < to)
+        })
+    }
+}
+
+/// Writes the export described by `options` and returns how many todos it
+/// contains.
+#[tauri::command]
+pub fn export_todos(options: ExportOptions, state: State<'_, AppState>) -> Result<usize, String> {
+    let path = Path::new(&options.path);
+    if !path.is_absolute() {
+        return Err(format!("export path must be absolute: {}", options.path));
+    }
+
+    let (content, count) = {
+        let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+        render(&data, &options)?
+    };
+
+    fs::write(path, content)
+        .map_err(|error| format!("failed to write export {}: {error}", path.display()))?;
+    log::info!("exported {count} todos to {}", path.display());
+    Ok(count)
+}
+
+/// The exported file and its number of todos.
+pub fn render(data: &AppData, options: &ExportOptions) -> Result<(String, usize), String> {
+    let columns = if options.columns.is_empty() {
+        Column::ALL.to_vec()
+    } else {
+        options.columns.clone()
+    };
+    let rows = ordered_todos(data)
+        .into_iter()
+        .map(|(todo, _)| todo)
+        .filter(|todo| options.filter.matches(todo))
+        .map(|todo| {
+            columns
+                .iter()
+                .map(|column| column.value(data, todo))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let content = match options.format {
+        ExportFormat::Csv => to_csv(&columns, &rows),
+        ExportFormat::Json => {
+            let objects = rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|column| column.name().to_string())
+                        .zip(row.iter().cloned())
+                        .collect::<Map<_, _>>()
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&objects)
+                .map_err(|error| format!("failed to serialize export: {error}"))?
+        }
+    };
+
+    Ok((content, rows.len()))
+}
+
+fn to_csv(columns: &[Column], rows: &[Vec<Value>]) -> String {
+    let mut output = String::new();
+    let header = columns
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect::<Vec<_>>();
+    push_record(&mut output, &header);
+
+    for row in rows {
+        let fields = row
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                Value::String(text) => escape_formula(text),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>();
+        push_record(&mut output, &fields);
+    }
+
+    output
+}
+
+/// Prefixes text starting like a formula with `'`, so that spreadsheets show
+/// it instead of running it.
+fn escape_formula(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    }
+}
+
+fn push_record(output: &mut String, fields: &[String]) {
+    let record = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    output.push_str(&record);
+    output.push_str("\r\n");
+}
+
+fn iso_value(millis: Option<i64>) -> Value {
+    millis
+        .and_then(|millis| Local.timestamp_millis_opt(millis).single())
+        .map_or(Value::Null, |date_time| {
+            Value::from(date_time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::TodoPriority;
+
+    fn todo(id: &str, title: &str, created_at: i64) -> Todo {
+        Todo {
+            sort_index: Some(created_at),
+            created_at,
+            ..Todo::for_test(id, title)
+        }
+    }
+
+    #[test]
+    fn filters_and_quotes_selected_columns() {
+        let mut data = AppData::default();
+        data.todos.push(Todo {
+            details: Some("Line one\nSay \"hi\"".to_string()),
+            priority: TodoPriority::High,
+            ..todo("a", "Plan, then ship", 1_000)
+        });
+        data.todos.push(Todo {
+            completed_at: Some(3_000),
+            ..todo("b", "Done already", 2_000)
+        });
+        data.todos.push(todo("c", "Too late", 5_000));
+        data.todos
+            .push(todo("d", "=HYPERLINK(\"http://example.com\")", 4_000));
+
+        let options = ExportOptions {
+            path: String::new(),
+            format: ExportFormat::Csv,
+            filter: TodoFilter {
+                completed: Some(false),
+                to: Some(5_000),
+                ..TodoFilter::default()
+            },
+            columns: vec![
+                Column::Title,
+                Column::Details,
+                Column::Priority,
+                Column::CompletedAt,
+            ],
+        };
+        let (csv, count) = render(&data, &options).expect("renders");
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            csv,
+            "title,details,priority,completedAt\r\n\
+             \"Plan, then ship\",\"Line one\nSay \"\"hi\"\"\",high,\r\n\
+             \"'=HYPERLINK(\"\"http://example.com\"\")\",,none,\r\n"
+        );
+
+        let options = ExportOptions {
+            format: ExportFormat::Json,
+            filter: TodoFilter {
+                date_field: DateField::Completed,
+                from: Some(3_000),
+                ..TodoFilter::default()
+            },
+            columns: vec![Column::Id, Column::CompletedAt],
+            ..options
+        };
+        let (json, count) = render(&data, &options).expect("renders");
+        let rows = serde_json::from_str::<Vec<Map<String, Value>>>(&json).expect("valid JSON");
+
+        assert_eq!(count, 1);
+        assert_eq!(rows[0]["id"], "b");
+        assert_eq!(rows[0]["completedAt"], iso_value(Some(3_000)));
+    }
+}
@@ -0,0 +1,361 @@
+//! Taskwarrior's JSON, as written by `task export` and read by `task import`:
+//! an array of task objects.
+//!
+//! - `uuid` is the todo id, kept on import unless a todo already has it; such
+//!   tasks are skipped, so importing the same file twice adds nothing.
+//! - `project` is the list and the first of `tags` the label, spaces written
+//!   as `_` since tags are single words.
+//! - `priority` is `H`, `M` or `L`. Taskwarrior has no higher level, so
+//!   urgent todos come back as high.
+//! - `entry` and `end` are the creation and completion times, `due` the
+//!   reminder and `wait` the start date.
+//! - A todo `depends` on its subtasks, as it is blocked until they are done.
+//!   On import a task becomes a subtask of the first task in the same list
+//!   that depends on it.
+//! - Details are written as one annotation; annotations are read back as
+//!   details, one per line.
+//!
+//! Deleted tasks and recurrence templates are not imported, and stars have
+//! no Taskwarrior equivalent.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use super::{export_lists, insert_by_list, list_trees, resolve_list, ImportedTodo, TodoTree};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, AppData, AppState, TodoPriority};
+
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Task {
+    #[serde(default)]
+    uuid: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wait: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Depends::is_empty")]
+    depends: Depends,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+}
+
+/// An array since Taskwarrior 2.6, a comma-separated string before.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Depends {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl Default for Depends {
+    fn default() -> Self {
+        Depends::List(Vec::new())
+    }
+}
+
+impl Depends {
+    fn is_empty(&self) -> bool {
+        self.uuids().next().is_none()
+    }
+
+    fn uuids(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        let uuids: Box<dyn Iterator<Item = &str>> = match self {
+            Depends::List(uuids) => Box::new(uuids.iter().map(String::as_str)),
+            Depends::Text(text) => Box::new(text.split(',')),
+        };
+        Box::new(uuids.map(str::trim).filter(|uuid| !uuid.is_empty()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    #[serde(default)]
+    description: String,
+}
+
+/// Exports one list, or every list when `list_id` is `None`.
+#[tauri::command]
+pub fn export_taskwarrior(
+    list_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+    to_taskwarrior(&data, list_id.as_deref())
+}
+
+/// Adds the tasks of `content` to the lists named by their `project`,
+/// creating missing lists, and to `list_id` when they have none.
+#[tauri::command]
+pub fn import_taskwarrior(
+    content: String,
+    list_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let count = import(&mut guard, &content, &list_id)?;
+        log::info!("imported {count} todos from Taskwarrior");
+    }
+
+    persist_state(&app, &state)
+}
+
+pub fn to_taskwarrior(data: &AppData, list_id: Option<&str>) -> Result<String, String> {
+    let lists = export_lists(data, list_id)?;
+
+    let mut tasks = Vec::new();
+    for list in lists {
+        for tree in list_trees(data, &list.id) {
+            push_tree(&mut tasks, data, &list.name, &tree);
+        }
+    }
+
+    serde_json::to_string_pretty(&tasks)
+        .map_err(|error| format!("failed to serialize Taskwarrior export: {error}"))
+}
+
+fn push_tree(tasks: &mut Vec<Task>, data: &AppData, project: &str, tree: &TodoTree) {
+    let todo = tree.todo;
+    let entry = format_time(todo.created_at);
+
+    tasks.push(Task {
+        uuid: todo.id.clone(),
+        description: todo.title.clone(),
+        status: if todo.completed_at.is_some() {
+            "completed"
+        } else {
+            "pending"
+        }
+        .to_string(),
+        end: todo.completed_at.and_then(format_time),
+        due: todo.reminder_at.and_then(format_time),
+        wait: todo.start_at.and_then(format_time),
+        project: Some(project.to_string()),
+        tags: todo
+            .label_id
+            .as_deref()
+            .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+            .map(|label| label.name.split_whitespace().collect::<Vec<_>>().join("_"))
+            .into_iter()
+            .collect(),
+        priority: match todo.priority {
+            TodoPriority::Urgent | TodoPriority::High => Some("H"),
+            TodoPriority::Medium => Some("M"),
+            TodoPriority::Low => Some("L"),
+            TodoPriority::None => None,
+        }
+        .map(str::to_string),
+        depends: Depends::List(
+            tree.children
+                .iter()
+                .map(|child| child.todo.id.clone())
+                .collect(),
+        ),
+        annotations: todo
+            .details
+            .iter()
+            .map(|details| Annotation {
+                entry: entry.clone(),
+                description: details.clone(),
+            })
+            .collect(),
+        entry,
+    });
+
+    for child in &tree.children {
+        push_tree(tasks, data, project, child);
+    }
+}
+
+/// Adds the tasks of `content` to `data` and returns how many todos were
+/// created.
+pub fn import(data: &mut AppData, content: &str, list_id: &str) -> Result<usize, String> {
+    if !data.settings.lists.iter().any(|list| list.id == list_id) {
+        return Err(format!("unknown list {list_id}"));
+    }
+
+    let tasks = serde_json::from_str::<Vec<Task>>(content)
+        .map_err(|error| format!("not a Taskwarrior export: {error}"))?
+        .into_iter()
+        .filter(|task| !matches!(task.status.as_str(), "deleted" | "recurring"))
+        .filter(|task| !data.todos.iter().any(|todo| todo.id == task.uuid))
+        .collect::<Vec<_>>();
+
+    let targets = tasks
+        .iter()
+        .map(|task| {
+            task.project
+                .as_deref()
+                .and_then(|name| resolve_list(data, name))
+                .unwrap_or_else(|| list_id.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    let mut dependents: HashMap<&str, usize> = HashMap::new();
+    for (index, task) in tasks.iter().enumerate() {
+        for uuid in task.depends.uuids() {
+            dependents.entry(uuid).or_insert(index);
+        }
+    }
+    let parents = tasks
+        .iter()
+        .enumerate()
+        .map(|(index, task)| {
+            dependents
+                .get(task.uuid.as_str())
+                .copied()
+                .filter(|parent| targets[*parent] == targets[index])
+        })
+        .collect::<Vec<_>>();
+
+    let now = now_millis();
+    let todos = tasks
+        .into_iter()
+        .map(|task| imported_todo(task, now))
+        .collect();
+    insert_by_list(data, todos, &targets, &parents)
+}
+
+fn imported_todo(task: Task, now: i64) -> ImportedTodo {
+    let details = task
+        .annotations
+        .iter()
+        .map(|annotation| annotation.description.trim())
+        .filter(|description| !description.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ImportedTodo {
+        id: Some(task.uuid),
+        title: task.description,
+        details: Some(details).filter(|details| !details.is_empty()),
+        priority: match task.priority.as_deref().map(str::trim) {
+            Some("H" | "h") => TodoPriority::High,
+            Some("M" | "m") => TodoPriority::Medium,
+            Some("L" | "l") => TodoPriority::Low,
+            _ => TodoPriority::None,
+        },
+        label: task
+            .tags
+            .iter()
+            .map(|tag| tag.replace('_', " ").trim().to_string())
+            .find(|tag| !tag.is_empty()),
+        created_at: task.entry.as_deref().and_then(parse_time),
+        completed_at: (task.status == "completed")
+            .then(|| task.end.as_deref().and_then(parse_time).unwrap_or(now)),
+        reminder_at: task.due.as_deref().and_then(parse_time),
+        start_at: task.wait.as_deref().and_then(parse_time),
+        ..ImportedTodo::default()
+    }
+}
+
+fn format_time(millis: i64) -> Option<String> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|date_time| date_time.format(TIME_FORMAT).to_string())
+}
+
+/// Taskwarrior's compact UTC form, or RFC 3339 as some scripts write.
+fn parse_time(value: &str) -> Option<i64> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, TIME_FORMAT)
+        .map(|naive| Utc.from_utc_datetime(&naive).timestamp_millis())
+        .or_else(|_| {
+            DateTime::parse_from_rfc3339(value).map(|date_time| date_time.timestamp_millis())
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DEFAULT_LIST_ID;
+
+    #[test]
+    fn round_trips_tasks_with_dependencies() {
+        let mut source = AppData::default();
+        let content = r#"[
+            {"id": 1, "uuid": "4a1c6f2e-1111-4b6e-9c1d-000000000001",
+             "description": "Prepare the release", "status": "pending",
+             "entry": "20260301T090000Z", "due": "20260305T170000Z",
+             "wait": "20260303T080000Z", "project": "Work", "tags": ["Deep_work"],
+             "priority": "H", "depends": "4a1c6f2e-1111-4b6e-9c1d-000000000002",
+             "annotations": [{"entry": "20260301T090000Z", "description": "Check CI"}],
+             "urgency": 12.3},
+            {"id": 0, "uuid": "4a1c6f2e-1111-4b6e-9c1d-000000000002",
+             "description": "Update the changelog", "status": "completed",
+             "entry": "20260301T090000Z", "end": "20260302T100000Z",
+             "project": "Work", "priority": "L"},
+            {"uuid": "4a1c6f2e-1111-4b6e-9c1d-000000000003",
+             "description": "Gone", "status": "deleted"},
+            {"uuid": "4a1c6f2e-1111-4b6e-9c1d-000000000004",
+             "description": "Inbox item", "status": "pending", "entry": "20260301T100000Z"}
+        ]"#;
+        let count = import(&mut source, content, DEFAULT_LIST_ID).expect("valid export");
+
+        assert_eq!(count, 3);
+        let release = &source.todos[0];
+        assert_eq!(release.id, "4a1c6f2e-1111-4b6e-9c1d-000000000001");
+        assert_eq!(release.priority, TodoPriority::High);
+        assert_eq!(release.details.as_deref(), Some("Check CI"));
+        assert_eq!(release.reminder_at, parse_time("20260305T170000Z"));
+        assert_eq!(release.start_at, parse_time("20260303T080000Z"));
+        let changelog = &source.todos[1];
+        assert_eq!(changelog.parent_id.as_deref(), Some(release.id.as_str()));
+        assert_eq!(changelog.completed_at, parse_time("20260302T100000Z"));
+        assert_eq!(source.todos[2].list_id.as_deref(), Some(DEFAULT_LIST_ID));
+        assert!(
+            source
+                .settings
+                .labels
+                .iter()
+                .any(|label| Some(&label.id) == release.label_id.as_ref()
+                    && label.name == "Deep work")
+        );
+
+        assert_eq!(import(&mut source, content, DEFAULT_LIST_ID), Ok(0));
+
+        let exported = to_taskwarrior(&source, None).expect("lists exist");
+        let mut copy = AppData::default();
+        import(&mut copy, &exported, DEFAULT_LIST_ID).expect("own export");
+
+        assert_eq!(copy.todos.len(), source.todos.len());
+        for todo in &source.todos {
+            let other = copy
+                .todos
+                .iter()
+                .find(|other| other.id == todo.id)
+                .expect("uuid kept");
+            assert_eq!(other.title, todo.title);
+            assert_eq!(other.details, todo.details);
+            assert_eq!(other.priority, todo.priority);
+            assert_eq!(other.parent_id, todo.parent_id);
+            assert_eq!(other.created_at, todo.created_at);
+            assert_eq!(other.completed_at, todo.completed_at);
+            assert_eq!(other.reminder_at, todo.reminder_at);
+            assert_eq!(other.start_at, todo.start_at);
+        }
+    }
+}
@@ -0,0 +1,603 @@
+//! [todo.txt](https://github.com/todotxt/todo.txt), one todo per line:
+//!
+//! ```text
+//! (B) 2026-03-01 Prepare the release +Work @Deep_work due:2026-03-05 id:1
+//! x 2026-03-02 2026-03-01 Update the changelog +Work p:1
+//! ```
+//!
+//! - Priorities `(A)` to `(D)` are urgent, high, medium and low; completed
+//!   todos keep theirs as `pri:`, as the format drops the prefix.
+//! - `+Project` is the list and `@Context` the label, spaces written as `_`.
+//!   When a line has several, the last one is used and the others stay in the
+//!   title.
+//! - Title words that read as a tag or key, like `@alice` or `p:2`, are
+//!   exported behind a backslash (`\@alice`) so that they stay in the title.
+//! - `due:` is the reminder and `t:` (threshold) the start date. Dates are
+//!   read as 09:00; other times are written as `YYYY-MM-DDTHH:MM`.
+//! - Subtasks use topydo's `id:`/`p:` keys: a todo with `p:1` belongs to the
+//!   earlier todo with `id:1` in the same list.
+//!
+//! Details and stars have no todo.txt equivalent and are not exported.
+
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDate, TimeZone, Timelike};
+use tauri::{AppHandle, State};
+
+use super::{
+    export_lists, format_local, insert_by_list, list_trees, resolve_list, ImportedTodo, TodoTree,
+};
+use crate::commands::{lock_error, persist_state};
+use crate::storage::{now_millis, parse_date_time, AppData, AppState, TodoPriority};
+
+/// Exports one list, or every list when `list_id` is `None`.
+#[tauri::command]
+pub fn export_todotxt(
+    list_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let data = state.data.lock().map_err(|_| lock_error("todo"))?;
+    to_todotxt(&data, list_id.as_deref())
+}
+
+/// Adds the todos of `content` to the lists named by their `+Project`,
+/// creating missing lists, and to `list_id` when they have none.
+#[tauri::command]
+pub fn import_todotxt(
+    content: String,
+    list_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppData, String> {
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let count = import(&mut guard, &content, &list_id)?;
+        log::info!("imported {count} todos from todo.txt");
+    }
+
+    persist_state(&app, &state)
+}
+
+pub fn to_todotxt(data: &AppData, list_id: Option<&str>) -> Result<String, String> {
+    let lists = export_lists(data, list_id)?;
+
+    let mut output = String::new();
+    let mut next_key = 1;
+    for list in lists {
+        let project = format!("+{}", token_name(&list.name));
+        for tree in list_trees(data, &list.id) {
+            write_tree(&mut output, data, &project, &tree, None, &mut next_key);
+        }
+    }
+
+    Ok(output)
+}
+
+fn write_tree(
+    output: &mut String,
+    data: &AppData,
+    project: &str,
+    tree: &TodoTree,
+    parent_key: Option<usize>,
+    next_key: &mut usize,
+) {
+    let todo = tree.todo;
+    let letter = priority_letter(todo.priority);
+    let mut words = Vec::new();
+
+    match todo.completed_at {
+        Some(completed_at) => {
+            words.push("x".to_string());
+            words.extend(format_date(completed_at));
+        }
+        None => words.extend(letter.map(|letter| format!("({letter})"))),
+    }
+    words.extend(format_date(todo.created_at));
+    words.extend(todo.title.split_whitespace().map(escape_word));
+    words.push(project.to_string());
+
+    if let Some(label) = todo
+        .label_id
+        .as_deref()
+        .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+    {
+        words.push(format!("@{}", token_name(&label.name)));
+    }
+    if let Some(reminder) = todo.reminder_at.and_then(format_moment) {
+        words.push(format!("due:{reminder}"));
+    }
+    if let Some(start) = todo.start_at.and_then(format_moment) {
+        words.push(format!("t:{start}"));
+    }
+    if let (Some(letter), Some(_)) = (letter, todo.completed_at) {
+        words.push(format!("pri:{letter}"));
+    }
+
+    let key = (!tree.children.is_empty()).then(|| {
+        let key = *next_key;
+        *next_key += 1;
+        key
+    });
+    if let Some(key) = key {
+        words.push(format!("id:{key}"));
+    }
+    if let Some(parent_key) = parent_key {
+        words.push(format!("p:{parent_key}"));
+    }
+
+    output.push_str(&words.join(" "));
+    output.push('\n');
+
+    for child in &tree.children {
+        write_tree(output, data, project, child, key, next_key);
+    }
+}
+
+/// Adds the todos of `content` to `data` as `import_todotxt` does and
+/// returns how many were created.
+pub fn import(data: &mut AppData, content: &str, list_id: &str) -> Result<usize, String> {
+    if !data.settings.lists.iter().any(|list| list.id == list_id) {
+        return Err(format!("unknown list {list_id}"));
+    }
+
+    let now = now_millis();
+    let lines = content
+        .lines()
+        .filter_map(|line| parse_line(line, now))
+        .collect::<Vec<_>>();
+
+    let mut line_lists = Vec::with_capacity(lines.len());
+    let mut parents = Vec::with_capacity(lines.len());
+    let mut keys: HashMap<&str, usize> = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let target = line
+            .project
+            .as_deref()
+            .and_then(|name| resolve_list(data, name))
+            .unwrap_or_else(|| list_id.to_string());
+
+        parents.push(
+            line.parent_key
+                .as_deref()
+                .and_then(|key| keys.get(key).copied())
+                .filter(|parent| line_lists[*parent] == target),
+        );
+        line_lists.push(target);
+        if let Some(key) = line.key.as_deref() {
+            keys.insert(key, index);
+        }
+    }
+
+    let todos = lines.into_iter().map(|line| line.todo).collect();
+    insert_by_list(data, todos, &line_lists, &parents)
+}
+
+struct Line {
+    todo: ImportedTodo,
+    project: Option<String>,
+    key: Option<String>,
+    parent_key: Option<String>,
+}
+
+fn parse_line(line: &str, now: i64) -> Option<Line> {
+    let mut words = line.split_whitespace().peekable();
+    words.peek()?;
+
+    let mut todo = ImportedTodo::default();
+    if words.next_if_eq(&"x").is_some() {
+        todo.completed_at = Some(
+            words
+                .next_if(|word| is_date(word))
+                .and_then(parse_date_time)
+                .unwrap_or(now),
+        );
+    }
+    if let Some(priority) = words.peek().and_then(|word| parse_priority_prefix(word)) {
+        todo.priority = priority;
+        words.next();
+    }
+    todo.created_at = words
+        .next_if(|word| is_date(word))
+        .and_then(parse_date_time);
+
+    let mut rest = words.collect::<Vec<_>>();
+    let project = take_last_tag(&mut rest, '+');
+    todo.label = take_last_tag(&mut rest, '@');
+
+    let mut key = None;
+    let mut parent_key = None;
+    let mut title = Vec::new();
+    for word in rest {
+        match parse_key(word) {
+            Some(Key::Due(reminder_at)) => todo.reminder_at = Some(reminder_at),
+            Some(Key::Threshold(start_at)) => todo.start_at = Some(start_at),
+            Some(Key::Priority(priority)) => todo.priority = priority,
+            Some(Key::Id(value)) => key = Some(value.to_string()),
+            Some(Key::Parent(value)) => parent_key = Some(value.to_string()),
+            None if is_escaped_token(word) => title.push(&word[1..]),
+            None => title.push(word),
+        }
+    }
+    todo.title = title.join(" ");
+
+    Some(Line {
+        todo,
+        project,
+        key,
+        parent_key,
+    })
+}
+
+enum Key<'a> {
+    Due(i64),
+    Threshold(i64),
+    Priority(TodoPriority),
+    Id(&'a str),
+    Parent(&'a str),
+}
+
+/// `due:`, `t:`, `pri:`, `id:` or `p:` with a valid value.
+fn parse_key(word: &str) -> Option<Key<'_>> {
+    match word.split_once(':')? {
+        ("due", value) => parse_date_time(value).map(Key::Due),
+        ("t", value) => parse_date_time(value).map(Key::Threshold),
+        ("pri", value) => parse_priority_letter(value).map(Key::Priority),
+        ("id", value) if !value.is_empty() => Some(Key::Id(value)),
+        ("p", value) if !value.is_empty() => Some(Key::Parent(value)),
+        _ => None,
+    }
+}
+
+/// `+Name` or `@Name`, with a name that is not only underscores.
+fn is_tag(word: &str, sigil: char) -> bool {
+    word.strip_prefix(sigil)
+        .is_some_and(|name| name.chars().any(|c| c != '_'))
+}
+
+/// Whether a title word would be read as a tag or key.
+fn is_token(word: &str) -> bool {
+    is_tag(word, '+') || is_tag(word, '@') || parse_key(word).is_some()
+}
+
+/// A token-like word behind one or more backslashes.
+fn is_escaped_token(word: &str) -> bool {
+    word.starts_with('\\') && is_token(word.trim_start_matches('\\'))
+}
+
+/// Escapes a title word that would be read as a tag or key, or is one already
+/// escaped, so that it stays part of the title.
+fn escape_word(word: &str) -> String {
+    if is_token(word) || is_escaped_token(word) {
+        format!("\\{word}")
+    } else {
+        word.to_string()
+    }
+}
+
+/// Removes the last `+Name` or `@Name` word and returns the name. Words
+/// without a name (`+`, `@_`) are left in the title.
+fn take_last_tag(words: &mut Vec<&str>, sigil: char) -> Option<String> {
+    let index = words.iter().rposition(|word| is_tag(word, sigil))?;
+    let word = words.remove(index);
+    Some(word[1..].replace('_', " "))
+}
+
+fn token_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+fn is_date(word: &str) -> bool {
+    word.len() == 10 && NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok()
+}
+
+fn format_date(millis: i64) -> Option<String> {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|date_time| date_time.format("%Y-%m-%d").to_string())
+}
+
+/// The date alone for 09:00, the time `parse_date_time` gives to dates.
+fn format_moment(millis: i64) -> Option<String> {
+    let date_time = Local.timestamp_millis_opt(millis).single()?;
+    if date_time.hour() == 9 && date_time.minute() == 0 {
+        format_date(millis)
+    } else {
+        format_local(millis)
+    }
+}
+
+fn priority_letter(priority: TodoPriority) -> Option<char> {
+    match priority {
+        TodoPriority::None => None,
+        TodoPriority::Urgent => Some('A'),
+        TodoPriority::High => Some('B'),
+        TodoPriority::Medium => Some('C'),
+        TodoPriority::Low => Some('D'),
+    }
+}
+
+/// `(A)` to `(Z)`.
+fn parse_priority_prefix(word: &str) -> Option<TodoPriority> {
+    word.strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .and_then(parse_priority_letter)
+}
+
+/// Letters past `D` are all low.
+fn parse_priority_letter(letter: &str) -> Option<TodoPriority> {
+    match letter {
+        "A" => Some(TodoPriority::Urgent),
+        "B" => Some(TodoPriority::High),
+        "C" => Some(TodoPriority::Medium),
+        _ if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) => {
+            Some(TodoPriority::Low)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::storage::{Todo, TodoLabel, TodoList, DEFAULT_LIST_ID};
+
+    const LIST_NAMES: [&str; 2] = ["Work", "Side project"];
+    const LABEL_NAMES: [&str; 2] = ["Deep work", "Errands"];
+    // 2026-01-01T12:00:00Z
+    const BASE_MILLIS: i64 = 1_767_268_800_000;
+    const DAY_MILLIS: i64 = 86_400_000;
+
+    fn reimport(text: &str) -> AppData {
+        let mut data = AppData::default();
+        import(&mut data, text, DEFAULT_LIST_ID).expect("default list exists");
+        data
+    }
+
+    fn export_all(data: &AppData) -> String {
+        to_todotxt(data, None).expect("lists exist")
+    }
+
+    /// Every todo in export order, with the fields todo.txt carries.
+    fn summary(data: &AppData) -> Vec<String> {
+        fn walk(data: &AppData, list: &str, tree: &TodoTree, depth: usize, out: &mut Vec<String>) {
+            let todo = tree.todo;
+            let label = todo
+                .label_id
+                .as_deref()
+                .and_then(|id| data.settings.labels.iter().find(|label| label.id == id))
+                .map(|label| label.name.clone());
+            out.push(format!(
+                "{list}/{depth} {} {:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+                todo.title,
+                todo.priority,
+                label,
+                format_date(todo.created_at),
+                todo.completed_at.and_then(format_date),
+                todo.reminder_at.and_then(format_local),
+                todo.start_at.and_then(format_local),
+                tree.children.len(),
+            ));
+            for child in &tree.children {
+                walk(data, list, child, depth + 1, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        for list in &data.settings.lists {
+            for tree in list_trees(data, &list.id) {
+                walk(data, &list.name, &tree, 0, &mut out);
+            }
+        }
+        out
+    }
+
+    #[derive(Debug, Clone)]
+    struct TodoSpec {
+        list: usize,
+        parent: Option<usize>,
+        title: String,
+        priority: TodoPriority,
+        label: Option<usize>,
+        created_day: i64,
+        completed_day: Option<i64>,
+        reminder_minutes: Option<i64>,
+        start_minutes: Option<i64>,
+    }
+
+    fn priority() -> impl Strategy<Value = TodoPriority> {
+        prop_oneof![
+            Just(TodoPriority::None),
+            Just(TodoPriority::Low),
+            Just(TodoPriority::Medium),
+            Just(TodoPriority::High),
+            Just(TodoPriority::Urgent),
+        ]
+    }
+
+    fn todo_spec() -> impl Strategy<Value = TodoSpec> {
+        (
+            0..=LIST_NAMES.len(),
+            proptest::option::of(0..16usize),
+            proptest::collection::vec(
+                "[a-z]{1,8}|[0-9]{4}-[0-9]{2}-[0-9]{2}|\\\\?[@+][a-z_]{1,4}\
+                 |\\\\?(due|t|id|p|pri|x):([a-z]{1,3}|[A-D]|2026-0[1-9]-1[0-9])",
+                1..5,
+            ),
+            priority(),
+            proptest::option::of(0..LABEL_NAMES.len()),
+            0..365i64,
+            proptest::option::of(0..365i64),
+            proptest::option::of(0..(365 * 24 * 60i64)),
+            proptest::option::of(0..(365 * 24 * 60i64)),
+        )
+            .prop_map(
+                |(
+                    list,
+                    parent,
+                    words,
+                    priority,
+                    label,
+                    created_day,
+                    completed_day,
+                    reminder_minutes,
+                    start_minutes,
+                )| TodoSpec {
+                    list,
+                    parent,
+                    title: words.join(" "),
+                    priority,
+                    label,
+                    created_day,
+                    completed_day,
+                    reminder_minutes,
+                    start_minutes,
+                },
+            )
+    }
+
+    /// Lists 1.. and every label are created by name; a spec's parent is an
+    /// earlier todo of the same list.
+    fn build_data(specs: &[TodoSpec]) -> AppData {
+        let mut data = AppData::default();
+        for (index, name) in LIST_NAMES.iter().enumerate() {
+            data.settings.lists.push(TodoList {
+                id: format!("list-{index}"),
+                name: name.to_string(),
+                icon: None,
+                notification_sound: None,
+                created_at: 0,
+            });
+        }
+        for (index, name) in LABEL_NAMES.iter().enumerate() {
+            data.settings.labels.push(TodoLabel {
+                id: format!("label-{index}"),
+                name: name.to_string(),
+                color: "slate".to_string(),
+            });
+        }
+
+        let list_ids = std::iter::once(DEFAULT_LIST_ID.to_string())
+            .chain((0..LIST_NAMES.len()).map(|index| format!("list-{index}")))
+            .collect::<Vec<_>>();
+
+        for (index, spec) in specs.iter().enumerate() {
+            let list_id = &list_ids[spec.list];
+            let parent_id = spec
+                .parent
+                .filter(|parent| *parent < index && specs[*parent].list == spec.list)
+                .map(|parent| format!("todo-{parent}"));
+
+            data.todos.push(Todo {
+                parent_id,
+                list_id: Some(list_id.clone()),
+                priority: spec.priority,
+                label_id: spec.label.map(|label| format!("label-{label}")),
+                sort_index: Some(index as i64),
+                created_at: BASE_MILLIS + spec.created_day * DAY_MILLIS,
+                completed_at: spec.completed_day.map(|day| BASE_MILLIS + day * DAY_MILLIS),
+                reminder_at: spec
+                    .reminder_minutes
+                    .map(|minutes| BASE_MILLIS + minutes * 60_000),
+                start_at: spec
+                    .start_minutes
+                    .map(|minutes| BASE_MILLIS + minutes * 60_000),
+                ..Todo::for_test(&format!("todo-{index}"), &spec.title)
+            });
+        }
+
+        data
+    }
+
+    fn line_word() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-z]{1,6}",
+            Just("x".to_string()),
+            Just("(A)".to_string()),
+            Just("(E)".to_string()),
+            Just("2026-03-01".to_string()),
+            Just("+Work".to_string()),
+            Just("+side_project".to_string()),
+            Just("+".to_string()),
+            Just("@Errands".to_string()),
+            Just("@_".to_string()),
+            Just("due:2026-03-05".to_string()),
+            Just("due:later".to_string()),
+            Just("t:2026-02-01T08:30".to_string()),
+            Just("pri:B".to_string()),
+            Just("id:1".to_string()),
+            Just("id:2".to_string()),
+            Just("p:1".to_string()),
+            Just("p:2".to_string()),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn exported_data_survives_a_round_trip(specs in proptest::collection::vec(todo_spec(), 0..16)) {
+            let data = build_data(&specs);
+            let text = export_all(&data);
+            let imported = reimport(&text);
+
+            prop_assert_eq!(summary(&imported), summary(&data));
+            prop_assert_eq!(export_all(&imported), text);
+        }
+
+        #[test]
+        fn any_text_is_stable_after_one_import(
+            lines in proptest::collection::vec(proptest::collection::vec(line_word(), 0..8), 0..10)
+        ) {
+            let text = lines
+                .iter()
+                .map(|words| words.join(" "))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let first = export_all(&reimport(&text));
+
+            prop_assert_eq!(export_all(&reimport(&first)), first);
+        }
+    }
+
+    #[test]
+    fn reads_standard_lines() {
+        let data = reimport(
+            "\
+(A) 2026-03-01 Call the bank +Home @Phone due:2026-03-05
+x 2026-03-02 2026-03-01 File taxes +Home pri:C
+Buy +milk and bread +Shopping
+",
+        );
+
+        let call = &data.todos[0];
+        assert_eq!(call.title, "Call the bank");
+        assert_eq!(call.priority, TodoPriority::Urgent);
+        assert_eq!(call.reminder_at, parse_date_time("2026-03-05"));
+        assert_eq!(
+            data.settings
+                .labels
+                .iter()
+                .find(|label| Some(&label.id) == call.label_id.as_ref())
+                .map(|label| label.name.as_str()),
+            Some("Phone")
+        );
+
+        let taxes = &data.todos[1];
+        assert_eq!(taxes.completed_at, parse_date_time("2026-03-02"));
+        assert_eq!(taxes.priority, TodoPriority::Medium);
+        assert_eq!(taxes.list_id, call.list_id);
+
+        assert_eq!(data.todos[2].title, "Buy +milk and bread");
+        assert!(data.settings.lists.iter().any(
+            |list| list.name == "Shopping" && Some(&list.id) == data.todos[2].list_id.as_ref()
+        ));
+
+        let data = reimport("Email \\@alice about \\p:2 due:2026-03-05 \\\\t:2026-03-01\n");
+        assert_eq!(data.todos[0].title, "Email @alice about p:2 \\t:2026-03-01");
+        assert_eq!(data.todos[0].label_id, None);
+        assert_eq!(data.todos[0].reminder_at, parse_date_time("2026-03-05"));
+        assert!(export_all(&data).contains(" Email \\@alice about \\p:2 \\\\t:2026-03-01 +"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Script hooks: local executables from `settings.scriptHooks` run when a
+//! todo event happens. Each run gets the `EventPayload` as JSON on stdin and
+//! `BLINKDO_EVENT` / `BLINKDO_TODO_ID` in its environment, is killed after
+//! its timeout, and has its exit status and output written to `blinkdo.log`.
+//! Runs go through a bounded queue drained by a few worker threads, so that a
+//! bulk import raising thousands of events never starts thousands of
+//! processes at once.
+//!
+//! Processes are started with `std::process` rather than the shell plugin's
+//! `Command`, which cannot close stdin while keeping the child to kill it.
+
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::events::{EventPayload, TodoEvent};
+use crate::storage::{ScriptHookSettings, Settings};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// Output beyond this is dropped from the log.
+const MAX_LOGGED_OUTPUT_BYTES: usize = 4 * 1024;
+// Hooks running at the same time.
+const WORKERS: usize = 2;
+// Runs waiting beyond this are skipped with a warning.
+const QUEUE_SIZE: usize = 256;
+
+/// Queue feeding the hook workers.
+#[derive(Default)]
+pub struct HookQueue {
+    sender: Mutex<Option<SyncSender<HookRun>>>,
+}
+
+struct HookRun {
+    hook: ScriptHookSettings,
+    todo_id: String,
+    input: Vec<u8>,
+}
+
+/// Starts the hook workers. Events raised before are dropped.
+pub fn start_workers(app: &AppHandle) {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+
+    if let Ok(mut queue_sender) = app.state::<HookQueue>().sender.lock() {
+        *queue_sender = Some(sender);
+    }
+
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..WORKERS {
+        let receiver = Arc::clone(&receiver);
+        thread::spawn(move || run_worker(&receiver));
+    }
+}
+
+/// Queues a run of every enabled hook subscribed to one of `events`, so that
+/// slow scripts never hold up saves.
+pub fn enqueue(app: &AppHandle, settings: &Settings, events: &[TodoEvent]) {
+    let Some(queue) = app.try_state::<HookQueue>() else {
+        return;
+    };
+    let Ok(sender) = queue.sender.lock() else {
+        return;
+    };
+    let Some(sender) = sender.as_ref() else {
+        return;
+    };
+
+    for hook in settings.script_hooks.iter().filter(|hook| hook.enabled) {
+        for event in events.iter().filter(|event| event.kind == hook.event) {
+            let input = match serde_json::to_vec(&EventPayload::new(event, settings)) {
+                Ok(input) => input,
+                Err(error) => {
+                    log::error!("failed to serialize script hook payload: {error}");
+                    continue;
+                }
+            };
+
+            let run = HookRun {
+                hook: hook.clone(),
+                todo_id: event.todo.id.clone(),
+                input,
+            };
+            match sender.try_send(run) {
+                Ok(()) => {}
+                Err(TrySendError::Full(run)) => log::warn!(
+                    "script hook {} ({}) for {} skipped, too many runs are waiting",
+                    run.hook.id,
+                    run.hook.event.name(),
+                    run.todo_id
+                ),
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+        }
+    }
+}
+
+fn run_worker(receiver: &Mutex<Receiver<HookRun>>) {
+    loop {
+        let run = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(run) = run else {
+            return;
+        };
+
+        run_hook(&run.hook, &run.todo_id, run.input);
+    }
+}
+
+fn run_hook(hook: &ScriptHookSettings, todo_id: &str, input: Vec<u8>) {
+    let started_at = Instant::now();
+    let mut command = Command::new(&hook.command);
+    // Own process group, so that a timeout also kills what the script started.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = match command
+        .args(&hook.args)
+        .env("BLINKDO_EVENT", hook.event.name())
+        .env("BLINKDO_TODO_ID", todo_id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(error) => {
+            log::error!(
+                "script hook {} failed to start {}: {error}",
+                hook.id,
+                hook.command
+            );
+            return;
+        }
+    };
+
+    // Scripts that do not read stdin must not block us on a full pipe.
+    if let Some(mut stdin) = child.stdin.take() {
+        thread::spawn(move || {
+            stdin.write_all(&input).ok();
+        });
+    }
+    let stdout = capture(child.stdout.take());
+    let stderr = capture(child.stderr.take());
+
+    let timeout = Duration::from_secs(u64::from(hook.timeout_seconds));
+    // Output is not awaited when the child was killed: processes left behind
+    // may keep the pipes open.
+    let status = match wait_with_timeout(&mut child, timeout) {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            log::warn!(
+                "script hook {} ({}) for {todo_id} killed after {}s timeout",
+                hook.id,
+                hook.event.name(),
+                hook.timeout_seconds
+            );
+            return;
+        }
+        Err(error) => {
+            log::error!(
+                "script hook {} ({}) for {todo_id} killed, failed to wait for it: {error}",
+                hook.id,
+                hook.event.name()
+            );
+            return;
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let elapsed = started_at.elapsed().as_millis();
+
+    if status.success() {
+        log::info!(
+            "script hook {} ({}) for {todo_id} exited after {elapsed} ms",
+            hook.id,
+            hook.event.name()
+        );
+    } else {
+        log::warn!(
+            "script hook {} ({}) for {todo_id} failed after {elapsed} ms: {status}",
+            hook.id,
+            hook.event.name()
+        );
+    }
+
+    if !stdout.is_empty() {
+        log::info!("script hook {} stdout: {stdout}", hook.id);
+    }
+    if !stderr.is_empty() {
+        log::warn!("script hook {} stderr: {stderr}", hook.id);
+    }
+}
+
+/// Returns `Ok(None)` when the child was killed after `timeout`, and the
+/// error when waiting failed, the child being killed too.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+
+    let result = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(Some(status)),
+            Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+            Ok(None) => break Ok(None),
+            Err(error) => break Err(error),
+        }
+    };
+
+    #[cfg(unix)]
+    // SAFETY: signals the process group created for this child only.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    child.kill().ok();
+    child.wait().ok();
+    result
+}
+
+fn capture<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            (&mut pipe)
+                .take(MAX_LOGGED_OUTPUT_BYTES as u64)
+                .read_to_end(&mut output)
+                .ok();
+            // Drained so that a chatty script never blocks on a full pipe.
+            io::copy(&mut pipe, &mut io::sink()).ok();
+        }
+
+        String::from_utf8_lossy(&output).trim().to_string()
+    })
+}
@@ -0,0 +1,682 @@
+//! Opt-in REST API on `127.0.0.1` for tools that can only speak HTTP.
+//!
+//! Every request needs `Authorization: Bearer <settings.httpApi.token>`.
+//! Resources are `/todos`, `/lists` and `/labels` (plus `/todos/{id}/complete`
+//! and `/search?q=`), and all changes go through the command functions or
+//! their helpers so validation and persistence match the app.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, Url};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::commands::{self, apply_todo_completed, lock_error, move_todo_tree, persist_state};
+use crate::storage::{
+    now_millis, resolve_list_id, AppData, AppState, HttpApiSettings, Todo, TodoPriority,
+};
+
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The server currently running, restarted when its settings change.
+#[derive(Default)]
+pub struct HttpApiServer {
+    running: Mutex<Option<RunningServer>>,
+}
+
+struct RunningServer {
+    settings: HttpApiSettings,
+    server: Arc<Server>,
+    thread: JoinHandle<()>,
+}
+
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+
+    fn not_found(what: &str, id: &str) -> Self {
+        Self::new(404, format!("unknown {what} {id}"))
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self::new(500, message)
+    }
+}
+
+type ApiResult = Result<(u16, Value), ApiError>;
+
+/// Starts, restarts or stops the server to match `settings`.
+pub fn apply_settings(app: &AppHandle, settings: &HttpApiSettings) {
+    let Some(state) = app.try_state::<HttpApiServer>() else {
+        return;
+    };
+
+    let previous = {
+        let Ok(mut running) = state.running.lock() else {
+            log::error!("failed to lock HTTP API state");
+            return;
+        };
+        if running
+            .as_ref()
+            .is_some_and(|running| running.settings == *settings)
+        {
+            return;
+        }
+        running.take()
+    };
+
+    if let Some(previous) = previous {
+        previous.server.unblock();
+        // A request changing the settings runs on the server thread itself.
+        if previous.thread.thread().id() != thread::current().id()
+            && previous.thread.join().is_err()
+        {
+            log::error!("HTTP API thread panicked");
+        }
+        log::info!("HTTP API stopped");
+    }
+
+    if !settings.enabled {
+        return;
+    }
+    if settings.token.is_empty() {
+        log::error!("HTTP API not started: no token configured");
+        return;
+    }
+
+    let server = match Server::http(("127.0.0.1", settings.port)) {
+        Ok(server) => Arc::new(server),
+        Err(error) => {
+            log::error!(
+                "failed to start HTTP API on port {}: {error}",
+                settings.port
+            );
+            return;
+        }
+    };
+
+    let thread = {
+        let app = app.clone();
+        let server = Arc::clone(&server);
+        let token = settings.token.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle_request(&app, &token, request);
+            }
+        })
+    };
+
+    log::info!("HTTP API listening on 127.0.0.1:{}", settings.port);
+    if let Ok(mut running) = state.running.lock() {
+        *running = Some(RunningServer {
+            settings: settings.clone(),
+            server,
+            thread,
+        });
+    };
+}
+
+fn handle_request(app: &AppHandle, token: &str, mut request: Request) {
+    let result = if is_authorized(&request, token) {
+        read_body(&mut request).and_then(|body| route(app, request.method(), request.url(), body))
+    } else {
+        Err(ApiError::new(401, "missing or invalid bearer token"))
+    };
+
+    let (status, body) = match result {
+        Ok((status, body)) => (status, body),
+        Err(error) => (error.status, json!({ "error": error.message })),
+    };
+
+    let response = if status == 204 {
+        Response::from_string(String::new()).with_status_code(status)
+    } else {
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json")
+                    .expect("static header is valid"),
+            )
+    };
+
+    if let Err(error) = request.respond(response) {
+        log::warn!("failed to send HTTP API response: {error}");
+    }
+}
+
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let expected = format!("Bearer {token}");
+
+    request
+        .headers()
+        .iter()
+        .filter(|header| header.field.equiv("Authorization"))
+        .any(|header| constant_time_eq(header.value.as_str(), &expected))
+}
+
+fn constant_time_eq(left: &str, right: &str) -> bool {
+    left.len() == right.len()
+        && left
+            .bytes()
+            .zip(right.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn read_body(request: &mut Request) -> Result<Option<Map<String, Value>>, ApiError> {
+    let mut raw = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut raw)
+        .map_err(|error| ApiError::bad_request(format!("failed to read body: {error}")))?;
+
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+
+    match serde_json::from_str::<Value>(&raw) {
+        Ok(Value::Object(body)) => Ok(Some(body)),
+        Ok(_) => Err(ApiError::bad_request("the body must be a JSON object")),
+        Err(error) => Err(ApiError::bad_request(format!("invalid JSON: {error}"))),
+    }
+}
+
+fn route(
+    app: &AppHandle,
+    method: &Method,
+    url: &str,
+    body: Option<Map<String, Value>>,
+) -> ApiResult {
+    let url = Url::parse(&format!("http://localhost{url}"))
+        .map_err(|error| ApiError::bad_request(format!("invalid URL: {error}")))?;
+    let segments = url
+        .path_segments()
+        .map(|segments| {
+            segments
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let query = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+    let body = body.unwrap_or_default();
+
+    let result = match (method, segments.as_slice()) {
+        (Method::Get, ["todos"]) => list_todos(app, &query),
+        (Method::Get, ["search"]) => search_todos(app, &query),
+        (Method::Post, ["todos"]) => create_todo(app, &body),
+        (Method::Get, ["todos", id]) => get_todo(app, id),
+        (Method::Patch, ["todos", id]) => update_todo(app, id, &body),
+        (Method::Delete, ["todos", id]) => delete_todo(app, id),
+        (Method::Post, ["todos", id, "complete"]) => complete_todo(app, id),
+        (Method::Get, ["lists"]) => ok(&snapshot(app).settings.lists),
+        (Method::Post, ["lists"]) => create_list(app, &body),
+        (Method::Get, ["lists", id]) => get_list(app, id),
+        (Method::Patch, ["lists", id]) => update_list(app, id, &body),
+        (Method::Delete, ["lists", id]) => delete_list(app, id),
+        (Method::Get, ["labels"]) => ok(&snapshot(app).settings.labels),
+        (Method::Post, ["labels"]) => create_label(app, &body),
+        (Method::Get, ["labels", id]) => get_label(app, id),
+        (Method::Patch, ["labels", id]) => update_label(app, id, &body),
+        (Method::Delete, ["labels", id]) => delete_label(app, id),
+        (_, ["todos" | "lists" | "labels" | "search", ..]) => {
+            Err(ApiError::new(405, "method not allowed"))
+        }
+        _ => Err(ApiError::new(404, "not found")),
+    };
+
+    result
+}
+
+fn snapshot(app: &AppHandle) -> AppData {
+    app.state::<AppState>().snapshot()
+}
+
+fn ok<T: Serialize + ?Sized>(value: &T) -> ApiResult {
+    with_status(200, value)
+}
+
+fn with_status<T: Serialize + ?Sized>(status: u16, value: &T) -> ApiResult {
+    serde_json::to_value(value)
+        .map(|value| (status, value))
+        .map_err(|error| ApiError::from(format!("failed to serialize response: {error}")))
+}
+
+/// `Some` when `name` is present in the body, so that `null` can clear a
+/// nullable field while an absent field leaves it unchanged.
+fn field<T: DeserializeOwned>(
+    body: &Map<String, Value>,
+    name: &str,
+) -> Result<Option<T>, ApiError> {
+    body.get(name)
+        .map(|value| {
+            serde_json::from_value(value.clone())
+                .map_err(|error| ApiError::bad_request(format!("invalid `{name}`: {error}")))
+        })
+        .transpose()
+}
+
+fn reject_unknown_fields(body: &Map<String, Value>, allowed: &[&str]) -> Result<(), ApiError> {
+    match body.keys().find(|key| !allowed.contains(&key.as_str())) {
+        Some(key) => Err(ApiError::bad_request(format!("unknown field `{key}`"))),
+        None => Ok(()),
+    }
+}
+
+fn find_todo(data: &AppData, id: &str) -> Result<Todo, ApiError> {
+    data.todos
+        .iter()
+        .find(|todo| todo.id == id)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("todo", id))
+}
+
+fn resolve_list(data: &AppData, list: &str) -> Result<String, ApiError> {
+    resolve_list_id(data, list).map_err(ApiError::bad_request)
+}
+
+fn matches_query(todo: &Todo, needle: &str) -> bool {
+    todo.title.to_lowercase().contains(needle)
+        || todo
+            .details
+            .as_deref()
+            .is_some_and(|details| details.to_lowercase().contains(needle))
+}
+
+/// `GET /todos?list=<id or name>&completed=<bool>&q=<text>`
+fn list_todos(app: &AppHandle, query: &HashMap<String, String>) -> ApiResult {
+    let data = snapshot(app);
+    let list_id = query
+        .get("list")
+        .map(|list| resolve_list(&data, list))
+        .transpose()?;
+    let completed = query
+        .get("completed")
+        .map(|value| {
+            value
+                .parse::<bool>()
+                .map_err(|_| ApiError::bad_request("`completed` must be true or false"))
+        })
+        .transpose()?;
+    let needle = query.get("q").map(|q| q.to_lowercase());
+
+    let todos = data
+        .todos
+        .iter()
+        .filter(|todo| {
+            list_id
+                .as_deref()
+                .map_or(true, |list_id| todo.list_id.as_deref() == Some(list_id))
+        })
+        .filter(|todo| completed.map_or(true, |completed| todo.completed_at.is_some() == completed))
+        .filter(|todo| {
+            needle
+                .as_deref()
+                .map_or(true, |needle| matches_query(todo, needle))
+        })
+        .collect::<Vec<_>>();
+
+    ok(&todos)
+}
+
+/// `GET /search?q=<text>`: open and completed todos matching the text.
+fn search_todos(app: &AppHandle, query: &HashMap<String, String>) -> ApiResult {
+    if query.get("q").map_or(true, |q| q.trim().is_empty()) {
+        return Err(ApiError::bad_request("missing `q`"));
+    }
+    list_todos(app, query)
+}
+
+fn get_todo(app: &AppHandle, id: &str) -> ApiResult {
+    ok(&find_todo(&snapshot(app), id)?)
+}
+
+fn create_todo(app: &AppHandle, body: &Map<String, Value>) -> ApiResult {
+    reject_unknown_fields(
+        body,
+        &["title", "details", "reminderAt", "parentId", "listId"],
+    )?;
+
+    let before = snapshot(app);
+    let list_id = field::<String>(body, "listId")?
+        .map(|list| resolve_list(&before, &list))
+        .transpose()?;
+
+    let after = commands::create_todo(
+        field(body, "title")?.ok_or_else(|| ApiError::bad_request("missing `title`"))?,
+        field(body, "details")?.flatten(),
+        field(body, "reminderAt")?.flatten(),
+        field(body, "parentId")?.flatten(),
+        list_id,
+        app.clone(),
+        app.state(),
+    )?;
+
+    let created = after
+        .todos
+        .iter()
+        .find(|todo| before.todos.iter().all(|previous| previous.id != todo.id))
+        .ok_or_else(|| ApiError::bad_request("the title must not be empty"))?;
+
+    with_status(201, created)
+}
+
+/// Fields of a `PATCH /todos/{id}`, `None` leaving a field unchanged.
+struct TodoPatch {
+    title: Option<String>,
+    details: Option<Option<String>>,
+    reminder_at: Option<Option<i64>>,
+    start_at: Option<Option<i64>>,
+    starred: Option<bool>,
+    priority: Option<TodoPriority>,
+    label_id: Option<Option<String>>,
+    list: Option<String>,
+    completed: Option<bool>,
+}
+
+impl TodoPatch {
+    fn parse(body: &Map<String, Value>) -> Result<Self, ApiError> {
+        reject_unknown_fields(
+            body,
+            &[
+                "title",
+                "details",
+                "reminderAt",
+                "startAt",
+                "starred",
+                "priority",
+                "labelId",
+                "listId",
+                "completed",
+            ],
+        )?;
+
+        let title = field::<String>(body, "title")?
+            .map(|title| title.trim().to_string())
+            .map(|title| {
+                if title.is_empty() {
+                    Err(ApiError::bad_request("the title must not be empty"))
+                } else {
+                    Ok(title)
+                }
+            })
+            .transpose()?;
+        let details = field::<Option<String>>(body, "details")?.map(|details| {
+            details
+                .map(|details| details.trim().to_string())
+                .filter(|details| !details.is_empty())
+        });
+        let label_id = field::<Option<String>>(body, "labelId")?.map(|label_id| {
+            label_id
+                .map(|label_id| label_id.trim().to_string())
+                .filter(|label_id| !label_id.is_empty())
+        });
+
+        Ok(Self {
+            title,
+            details,
+            reminder_at: field(body, "reminderAt")?,
+            start_at: field(body, "startAt")?,
+            starred: field(body, "starred")?,
+            priority: field(body, "priority")?,
+            label_id,
+            list: field(body, "listId")?,
+            completed: field(body, "completed")?,
+        })
+    }
+}
+
+/// `PATCH /todos/{id}` with any of `title`, `details`, `reminderAt`,
+/// `startAt`, `starred`, `priority`, `labelId`, `listId` and `completed`.
+/// Every field is validated first, then all of them are applied in one
+/// change and saved once, or none when one is invalid.
+fn update_todo(app: &AppHandle, id: &str, body: &Map<String, Value>) -> ApiResult {
+    let patch = TodoPatch::parse(body)?;
+    let state = app.state::<AppState>();
+
+    let (reminder_changed, start_changed, completed_ids) = {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let index = guard
+            .todos
+            .iter()
+            .position(|todo| todo.id == id)
+            .ok_or_else(|| ApiError::not_found("todo", id))?;
+        let list_id = patch
+            .list
+            .as_deref()
+            .map(|list| resolve_list(&guard, list))
+            .transpose()?;
+        if let Some(Some(label_id)) = &patch.label_id {
+            if !guard
+                .settings
+                .labels
+                .iter()
+                .any(|label| label.id == *label_id)
+            {
+                return Err(ApiError::bad_request(format!("unknown label {label_id}")));
+            }
+        }
+
+        let todo = &mut guard.todos[index];
+        let reminder_changed = patch
+            .reminder_at
+            .is_some_and(|reminder_at| reminder_at != todo.reminder_at);
+        let start_changed = patch
+            .start_at
+            .is_some_and(|start_at| start_at != todo.start_at);
+
+        if let Some(title) = patch.title {
+            todo.title = title;
+        }
+        if let Some(details) = patch.details {
+            todo.details = details;
+        }
+        if let Some(reminder_at) = patch.reminder_at {
+            todo.reminder_at = reminder_at;
+        }
+        if let Some(start_at) = patch.start_at {
+            todo.start_at = start_at;
+        }
+        if let Some(starred) = patch.starred {
+            todo.starred = starred;
+        }
+        if let Some(priority) = patch.priority {
+            todo.priority = priority;
+        }
+        if let Some(label_id) = patch.label_id {
+            todo.label_id = label_id;
+        }
+        todo.updated_at = Some(now_millis());
+
+        if let Some(list_id) = list_id {
+            move_todo_tree(&mut guard, id, &list_id);
+        }
+        let mut completed_ids = HashSet::new();
+        if let Some(completed) = patch.completed {
+            let ids = apply_todo_completed(&mut guard, id, completed);
+            if completed {
+                completed_ids = ids;
+            }
+        }
+
+        (reminder_changed, start_changed, completed_ids)
+    };
+
+    if reminder_changed || !completed_ids.is_empty() {
+        let mut notified = state
+            .notified_todos
+            .lock()
+            .map_err(|_| lock_error("reminder"))?;
+        if reminder_changed {
+            notified.remove(id);
+        }
+        for completed_id in &completed_ids {
+            notified.remove(completed_id);
+        }
+    }
+    if start_changed {
+        let mut notified = state
+            .available_notified
+            .lock()
+            .map_err(|_| lock_error("reminder"))?;
+        notified.remove(id);
+    }
+
+    let data = persist_state(app, &state)?;
+    ok(&find_todo(&data, id)?)
+}
+
+fn delete_todo(app: &AppHandle, id: &str) -> ApiResult {
+    find_todo(&snapshot(app), id)?;
+    commands::delete_todo(id.to_string(), app.clone(), app.state())?;
+    Ok((204, Value::Null))
+}
+
+fn complete_todo(app: &AppHandle, id: &str) -> ApiResult {
+    find_todo(&snapshot(app), id)?;
+    let data = commands::set_todo_completed(id.to_string(), true, app.clone(), app.state())?;
+    ok(&find_todo(&data, id)?)
+}
+
+fn get_list(app: &AppHandle, id: &str) -> ApiResult {
+    let data = snapshot(app);
+    let list = data
+        .settings
+        .lists
+        .iter()
+        .find(|list| list.id == id)
+        .ok_or_else(|| ApiError::not_found("list", id))?;
+    ok(list)
+}
+
+fn create_list(app: &AppHandle, body: &Map<String, Value>) -> ApiResult {
+    reject_unknown_fields(body, &["name", "icon"])?;
+    let name = field::<String>(body, "name")?.unwrap_or_default();
+    let icon = field::<Option<String>>(body, "icon")?;
+
+    let state = app.state::<AppState>();
+    let id = {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        let id = commands::insert_list(&mut guard, &name);
+        commands::edit_list(&mut guard, &id, None, icon, None);
+        id
+    };
+
+    persist_state(app, &state)?;
+    let (_, list) = get_list(app, &id)?;
+    Ok((201, list))
+}
+
+/// `PATCH /lists/{id}` with any of `name`, `icon` and `notificationSound`,
+/// all decoded before any is applied.
+fn update_list(app: &AppHandle, id: &str, body: &Map<String, Value>) -> ApiResult {
+    reject_unknown_fields(body, &["name", "icon", "notificationSound"])?;
+    let name = field::<String>(body, "name")?;
+    let icon = field::<Option<String>>(body, "icon")?;
+    let sound = field::<Option<String>>(body, "notificationSound")?;
+
+    let state = app.state::<AppState>();
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        if !commands::edit_list(&mut guard, id, name.as_deref(), icon, sound) {
+            return Err(ApiError::not_found("list", id));
+        }
+    }
+
+    persist_state(app, &state)?;
+    get_list(app, id)
+}
+
+/// Same as deleting a list in the settings: its todos move to the active list.
+fn delete_list(app: &AppHandle, id: &str) -> ApiResult {
+    let state = app.state::<AppState>();
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        if !guard.settings.lists.iter().any(|list| list.id == id) {
+            return Err(ApiError::not_found("list", id));
+        }
+        if !commands::remove_list(&mut guard, id) {
+            return Err(ApiError::new(409, "the last list cannot be deleted"));
+        }
+    }
+
+    persist_state(app, &state)?;
+    Ok((204, Value::Null))
+}
+
+fn get_label(app: &AppHandle, id: &str) -> ApiResult {
+    let data = snapshot(app);
+    let label = data
+        .settings
+        .labels
+        .iter()
+        .find(|label| label.id == id)
+        .ok_or_else(|| ApiError::not_found("label", id))?;
+    ok(label)
+}
+
+fn create_label(app: &AppHandle, body: &Map<String, Value>) -> ApiResult {
+    reject_unknown_fields(body, &["name", "color"])?;
+    let name = field::<String>(body, "name")?.unwrap_or_default();
+    let color = field::<String>(body, "color")?.unwrap_or_default();
+
+    let state = app.state::<AppState>();
+    let id = {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        commands::insert_label(&mut guard, &name, &color)
+    };
+
+    persist_state(app, &state)?;
+    let (_, label) = get_label(app, &id)?;
+    Ok((201, label))
+}
+
+fn update_label(app: &AppHandle, id: &str, body: &Map<String, Value>) -> ApiResult {
+    reject_unknown_fields(body, &["name", "color"])?;
+    let name = field::<String>(body, "name")?;
+    let color = field::<String>(body, "color")?;
+
+    let state = app.state::<AppState>();
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        if !commands::edit_label(&mut guard, id, name.as_deref(), color.as_deref()) {
+            return Err(ApiError::not_found("label", id));
+        }
+    }
+
+    persist_state(app, &state)?;
+    get_label(app, id)
+}
+
+fn delete_label(app: &AppHandle, id: &str) -> ApiResult {
+    let state = app.state::<AppState>();
+    {
+        let mut guard = state.data.lock().map_err(|_| lock_error("todo"))?;
+        if !commands::remove_label(&mut guard, id) {
+            return Err(ApiError::not_found("label", id));
+        }
+    }
+
+    persist_state(app, &state)?;
+    Ok((204, Value::Null))
+}
@@ -0,0 +1,181 @@
+use crate::storage::TodoPriority;
+
+/// Backend strings shown outside the webviews (notifications, tray menu and
+/// default names). Templates use `{placeholder}` tokens filled by [`fill`].
+pub struct Messages {
+    pub reminder_title: &'static str,
+    pub available_title: &'static str,
+    pub daily_summary_title: &'static str,
+    pub daily_summary_empty: &'static str,
+    pub daily_summary_open: &'static str,
+    pub daily_summary_list_count: &'static str,
+    pub daily_summary_overdue: &'static str,
+    pub daily_summary_starred: &'static str,
+    pub daily_summary_reminders: &'static str,
+    pub tray_show_main: &'static str,
+    pub tray_toggle_overlay: &'static str,
+    pub tray_quit: &'static str,
+    pub default_list_name: &'static str,
+    pub new_list_name: &'static str,
+    pub default_label_name: &'static str,
+    pub priority_low: &'static str,
+    pub priority_medium: &'static str,
+    pub priority_high: &'static str,
+    pub priority_urgent: &'static str,
+}
+
+impl Messages {
+    pub fn priority(&self, priority: TodoPriority) -> Option<&'static str> {
+        match priority {
+            TodoPriority::None => None,
+            TodoPriority::Low => Some(self.priority_low),
+            TodoPriority::Medium => Some(self.priority_medium),
+            TodoPriority::High => Some(self.priority_high),
+            TodoPriority::Urgent => Some(self.priority_urgent),
+        }
+    }
+}
+
+static EN: Messages = Messages {
+    reminder_title: "Task reminder",
+    available_title: "Task available",
+    daily_summary_title: "Daily summary",
+    daily_summary_empty: "No open tasks",
+    daily_summary_open: "{count} open task(s)",
+    daily_summary_list_count: "{name}: {count}",
+    daily_summary_overdue: "Overdue: {count}",
+    daily_summary_starred: "Starred: {count}",
+    daily_summary_reminders: "Today's reminders: {items}",
+    tray_show_main: "Show main window",
+    tray_toggle_overlay: "Show / Hide overlay",
+    tray_quit: "Quit",
+    default_list_name: "My tasks",
+    new_list_name: "New list",
+    default_label_name: "General",
+    priority_low: "Low",
+    priority_medium: "Medium",
+    priority_high: "High",
+    priority_urgent: "Urgent",
+};
+
+static FR: Messages = Messages {
+    reminder_title: "Rappel tâche",
+    available_title: "Tâche disponible",
+    daily_summary_title: "Résumé du jour",
+    daily_summary_empty: "Aucune tâche ouverte",
+    daily_summary_open: "{count} tâche(s) ouverte(s)",
+    daily_summary_list_count: "{name} : {count}",
+    daily_summary_overdue: "En retard : {count}",
+    daily_summary_starred: "Favoris : {count}",
+    daily_summary_reminders: "Rappels aujourd'hui : {items}",
+    tray_show_main: "Afficher fenêtre principale",
+    tray_toggle_overlay: "Afficher / Masquer overlay",
+    tray_quit: "Quitter",
+    default_list_name: "Mes tâches",
+    new_list_name: "Nouvelle liste",
+    default_label_name: "Général",
+    priority_low: "Basse",
+    priority_medium: "Moyenne",
+    priority_high: "Haute",
+    priority_urgent: "Urgent",
+};
+
+static ES: Messages = Messages {
+    reminder_title: "Recordatorio de tarea",
+    available_title: "Tarea disponible",
+    daily_summary_title: "Resumen del día",
+    daily_summary_empty: "No hay tareas abiertas",
+    daily_summary_open: "{count} tarea(s) abierta(s)",
+    daily_summary_list_count: "{name}: {count}",
+    daily_summary_overdue: "Atrasadas: {count}",
+    daily_summary_starred: "Favoritas: {count}",
+    daily_summary_reminders: "Recordatorios de hoy: {items}",
+    tray_show_main: "Mostrar ventana principal",
+    tray_toggle_overlay: "Mostrar / Ocultar overlay",
+    tray_quit: "Salir",
+    default_list_name: "Mis tareas",
+    new_list_name: "Nueva lista",
+    default_label_name: "General",
+    priority_low: "Baja",
+    priority_medium: "Media",
+    priority_high: "Alta",
+    priority_urgent: "Urgente",
+};
+
+static ZH: Messages = Messages {
+    reminder_title: "任务提醒",
+    available_title: "任务已可开始",
+    daily_summary_title: "每日摘要",
+    daily_summary_empty: "没有未完成的任务",
+    daily_summary_open: "{count} 个未完成任务",
+    daily_summary_list_count: "{name}：{count}",
+    daily_summary_overdue: "已逾期：{count}",
+    daily_summary_starred: "已加星标：{count}",
+    daily_summary_reminders: "今日提醒：{items}",
+    tray_show_main: "显示主窗口",
+    tray_toggle_overlay: "显示 / 隐藏浮层",
+    tray_quit: "退出",
+    default_list_name: "我的任务",
+    new_list_name: "新列表",
+    default_label_name: "常规",
+    priority_low: "低",
+    priority_medium: "中",
+    priority_high: "高",
+    priority_urgent: "紧急",
+};
+
+static HI: Messages = Messages {
+    reminder_title: "कार्य अनुस्मारक",
+    available_title: "कार्य उपलब्ध",
+    daily_summary_title: "दैनिक सारांश",
+    daily_summary_empty: "कोई खुला कार्य नहीं",
+    daily_summary_open: "{count} खुले कार्य",
+    daily_summary_list_count: "{name}: {count}",
+    daily_summary_overdue: "विलंबित: {count}",
+    daily_summary_starred: "तारांकित: {count}",
+    daily_summary_reminders: "आज के अनुस्मारक: {items}",
+    tray_show_main: "मुख्य विंडो दिखाएँ",
+    tray_toggle_overlay: "ओवरले दिखाएँ / छिपाएँ",
+    tray_quit: "बाहर निकलें",
+    default_list_name: "मेरे कार्य",
+    new_list_name: "नई सूची",
+    default_label_name: "सामान्य",
+    priority_low: "निम्न",
+    priority_medium: "मध्यम",
+    priority_high: "उच्च",
+    priority_urgent: "तत्काल",
+};
+
+/// Resolves `Settings.language` (`auto`, `fr`, `en-US`, ...) to a catalog,
+/// using the system locale for `auto` and English for anything unsupported.
+pub fn messages(language: &str) -> &'static Messages {
+    let requested = language.trim();
+    let resolved = if requested.is_empty() || requested.eq_ignore_ascii_case("auto") {
+        sys_locale::get_locale().unwrap_or_default()
+    } else {
+        requested.to_string()
+    };
+
+    let primary = resolved
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match primary.as_str() {
+        "fr" => &FR,
+        "es" => &ES,
+        "zh" => &ZH,
+        "hi" => &HI,
+        _ => &EN,
+    }
+}
+
+/// Replaces each `{key}` token of `template` with its value.
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{key}}}"), value)
+        })
+}
@@ -0,0 +1,140 @@
+//! Launch arguments understood by the app itself, applied on startup and when
+//! a second launch forwards its arguments to the running instance.
+
+use tauri::{AppHandle, Manager};
+
+use crate::commands;
+use crate::deep_link;
+use crate::storage::AppState;
+use crate::window;
+
+#[derive(Debug, Default, PartialEq)]
+struct LaunchArgs {
+    overlay: bool,
+    autostart: bool,
+    deep_link: bool,
+    add: Vec<String>,
+}
+
+/// Parses `args` without the program name. Unknown arguments are ignored so
+/// that launchers adding their own flags do not break startup.
+fn parse(args: &[String]) -> LaunchArgs {
+    let mut parsed = LaunchArgs::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--overlay" => parsed.overlay = true,
+            "--autostart" => parsed.autostart = true,
+            "--add" => {
+                if let Some(title) = args.next() {
+                    parsed.add.push(title.clone());
+                }
+            }
+            other => {
+                if let Some(title) = other.strip_prefix("--add=") {
+                    parsed.add.push(title.to_string());
+                } else if deep_link::is_deep_link(other) {
+                    parsed.deep_link = true;
+                }
+            }
+        }
+    }
+
+    parsed
+}
+
+/// Applies the arguments of the first launch.
+pub fn handle_startup_args(app: &AppHandle, args: &[String]) {
+    let parsed = parse(args);
+    add_todos(app, &parsed.add);
+
+    if parsed.overlay {
+        if let Err(error) = window::show_overlay_window(app) {
+            log::error!("failed to show overlay: {error}");
+        }
+    }
+}
+
+/// Applies the arguments forwarded by a second launch, which then exits.
+/// A bare launch brings the main window back; `blinkdo://` links are handled
+/// by the deep-link plugin.
+pub fn handle_forwarded_args(app: &AppHandle, args: &[String]) {
+    let parsed = parse(args);
+    log::info!("arguments forwarded by a second launch: {parsed:?}");
+
+    add_todos(app, &parsed.add);
+
+    let result = if parsed.overlay {
+        window::show_overlay_window(app)
+    } else if parsed.autostart || parsed.deep_link {
+        Ok(())
+    } else {
+        window::show_main_window(app)
+    };
+
+    if let Err(error) = result {
+        log::error!("failed to show window for second launch: {error}");
+    }
+}
+
+fn add_todos(app: &AppHandle, titles: &[String]) {
+    if titles.is_empty() {
+        return;
+    }
+
+    for title in titles {
+        if let Err(error) = commands::create_todo(
+            title.clone(),
+            None,
+            None,
+            None,
+            None,
+            app.clone(),
+            app.state::<AppState>(),
+        ) {
+            log::error!("failed to add todo from launch arguments: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_overlay_and_add_flags() {
+        let parsed = parse(&args(&["--overlay", "--add", "Buy milk", "--add=Call Bob"]));
+
+        assert_eq!(
+            parsed,
+            LaunchArgs {
+                overlay: true,
+                autostart: false,
+                deep_link: false,
+                add: vec!["Buy milk".to_string(), "Call Bob".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_and_incomplete_arguments() {
+        let parsed = parse(&args(&["--autostart", "-psn_0_1234", "--add"]));
+
+        assert!(parsed.autostart);
+        assert!(!parsed.overlay);
+        assert!(parsed.add.is_empty());
+    }
+
+    #[test]
+    fn detects_forwarded_links() {
+        let parsed = parse(&args(&["blinkdo://add?title=Milk"]));
+
+        assert!(parsed.deep_link);
+        assert!(parsed.add.is_empty());
+    }
+}
@@ -0,0 +1,465 @@
+//! Local control socket for scripts, editor plugins and the command-line
+//! client. The running app listens on a Unix domain socket in its data
+//! directory (a named pipe on Windows) that only the current user can open.
+//!
+//! The protocol is JSON-RPC 2.0 with one JSON document per line. Methods map
+//! onto the `#[tauri::command]` functions and take the same camelCase
+//! arguments as the frontend. `subscribe` turns the connection into an event
+//! stream receiving a `stateChanged` notification after every save.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+use interprocess::local_socket::{prelude::*, ListenerOptions, Name};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::commands;
+use crate::reminder;
+use crate::storage::{self, AppData, AppState};
+use crate::window;
+
+#[cfg(unix)]
+const SOCKET_FILE_NAME: &str = "blinkdo.sock";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const COMMAND_FAILED: i64 = -32000;
+
+/// Senders of the connections that called `subscribe`.
+#[derive(Default)]
+pub struct IpcSubscribers {
+    senders: Mutex<Vec<Sender<String>>>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(COMMAND_FAILED, message)
+    }
+}
+
+#[cfg(unix)]
+fn socket_name(data_dir: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+
+    data_dir
+        .join(SOCKET_FILE_NAME)
+        .to_fs_name::<GenericFilePath>()
+        .map(Name::into_owned)
+}
+
+#[cfg(windows)]
+fn socket_name(_data_dir: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+
+    // Pipe names are machine-wide, so each user gets their own.
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    format!("blinkdo-{user}.sock")
+        .to_ns_name::<GenericNamespaced>()
+        .map(Name::into_owned)
+}
+
+#[cfg(unix)]
+fn listener_options(name: Name<'_>) -> io::Result<ListenerOptions<'_>> {
+    // The socket file is replaced when left over by a crash; the instance
+    // lock guarantees that no other app is listening on it.
+    Ok(ListenerOptions::new().name(name).try_overwrite(true))
+}
+
+#[cfg(windows)]
+fn listener_options(name: Name<'_>) -> io::Result<ListenerOptions<'_>> {
+    use interprocess::os::windows::{
+        local_socket::ListenerOptionsExt, security_descriptor::SecurityDescriptor,
+    };
+    use widestring::u16cstr;
+
+    // Full access for the pipe owner (the current user) and SYSTEM only,
+    // instead of the default read access for everyone.
+    let descriptor = SecurityDescriptor::deserialize(u16cstr!("D:P(A;;GA;;;OW)(A;;GA;;;SY)"))?;
+    Ok(ListenerOptions::new()
+        .name(name)
+        .security_descriptor(descriptor))
+}
+
+/// Rejects peers running as another user.
+#[cfg(unix)]
+fn authorize(stream: &LocalSocketStream) -> io::Result<()> {
+    let peer_uid = stream.peer_creds()?.euid();
+
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let own_uid = unsafe { libc::geteuid() };
+
+    if peer_uid == Some(own_uid) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "peer runs as another user",
+        ))
+    }
+}
+
+/// Access is already restricted by the pipe's security descriptor.
+#[cfg(windows)]
+fn authorize(_stream: &LocalSocketStream) -> io::Result<()> {
+    Ok(())
+}
+
+/// Starts listening in the background. Only called by the instance that holds
+/// the instance lock.
+pub fn start_server(app: AppHandle) -> Result<(), String> {
+    let data_dir = storage::app_data_dir(&app)?;
+    let listener = socket_name(&data_dir)
+        .and_then(|name| listener_options(name)?.create_sync())
+        .map_err(|error| format!("failed to create IPC socket: {error}"))?;
+
+    app.manage(IpcSubscribers::default());
+
+    thread::spawn(move || {
+        for connection in listener.incoming() {
+            match connection {
+                Ok(stream) => {
+                    if let Err(error) = authorize(&stream) {
+                        log::warn!("rejected IPC connection: {error}");
+                        continue;
+                    }
+
+                    let app = app.clone();
+                    thread::spawn(move || serve_connection(app, stream));
+                }
+                Err(error) => log::error!("failed to accept IPC connection: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Sends the saved data to every subscribed connection.
+pub fn publish_change(app: &AppHandle, data: &AppData) {
+    let Some(subscribers) = app.try_state::<IpcSubscribers>() else {
+        return;
+    };
+    let Ok(mut senders) = subscribers.senders.lock() else {
+        return;
+    };
+    if senders.is_empty() {
+        return;
+    }
+
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": "stateChanged",
+        "params": data,
+    })
+    .to_string();
+
+    senders.retain(|sender| sender.send(notification.clone()).is_ok());
+}
+
+fn serve_connection(app: AppHandle, stream: LocalSocketStream) {
+    let (reader, mut writer) = stream.split();
+    let (sender, receiver) = mpsc::channel::<String>();
+
+    // Responses and notifications share one writer so lines never interleave.
+    thread::spawn(move || {
+        for line in receiver {
+            if writeln!(writer, "{line}")
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_line(&app, &line, &sender) {
+            if sender.send(response).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the serialized response, or `None` for notifications (requests
+/// without an `id`).
+fn handle_line(app: &AppHandle, line: &str, sender: &Sender<String>) -> Option<String> {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(error) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, error.to_string()),
+            ))
+        }
+    };
+
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            RpcError::new(INVALID_REQUEST, "missing method"),
+        ));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = if method == "subscribe" {
+        subscribe(app, sender)
+    } else {
+        dispatch(app, method, &params)
+    };
+
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+    .to_string()
+}
+
+fn subscribe(app: &AppHandle, sender: &Sender<String>) -> Result<Value, RpcError> {
+    let subscribers = app.state::<IpcSubscribers>();
+    subscribers
+        .senders
+        .lock()
+        .map_err(|_| String::from("failed to lock IPC subscribers"))?
+        .push(sender.clone());
+
+    reply(Ok(commands::load_state(app.state())))
+}
+
+fn param<T: DeserializeOwned>(params: &Value, name: &str) -> Result<T, RpcError> {
+    let value = params.get(name).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value)
+        .map_err(|error| RpcError::new(INVALID_PARAMS, format!("invalid `{name}`: {error}")))
+}
+
+fn reply<T: Serialize>(result: Result<T, String>) -> Result<Value, RpcError> {
+    let value = result?;
+    serde_json::to_value(value)
+        .map_err(|error| RpcError::from(format!("failed to serialize result: {error}")))
+}
+
+fn dispatch(app: &AppHandle, method: &str, params: &Value) -> Result<Value, RpcError> {
+    let handle = || app.clone();
+    let state = || app.state::<AppState>();
+    let p = params;
+
+    match method {
+        "ping" => Ok(json!("pong")),
+        "load_state" => reply(Ok(commands::load_state(state()))),
+        "load_state_since" => reply(commands::load_state_since(param(p, "revision")?, state())),
+        "query_todos" => reply(commands::query_todos(param(p, "listId")?, state())),
+        "get_app_version" => reply(Ok(commands::get_app_version())),
+        "preview_daily_summary" => reply(Ok(reminder::preview_daily_summary(state()))),
+        "acknowledge_reminder" => reply(reminder::acknowledge_reminder(param(p, "id")?, state())),
+        "show_overlay" => {
+            reply(window::show_overlay_window(app).map_err(|error| error.to_string()))
+        }
+        "hide_overlay" => reply(commands::hide_overlay(handle())),
+        "toggle_overlay" => reply(window::toggle_overlay(app).map_err(|error| error.to_string())),
+        "add_todo" => reply(commands::add_todo(param(p, "text")?, handle(), state())),
+        "create_todo" => reply(commands::create_todo(
+            param(p, "title")?,
+            param(p, "details")?,
+            param(p, "reminderAt")?,
+            param(p, "parentId")?,
+            param(p, "listId")?,
+            handle(),
+            state(),
+        )),
+        "update_todo" => reply(commands::update_todo(
+            param(p, "payload")?,
+            handle(),
+            state(),
+        )),
+        "complete_todo" => reply(commands::complete_todo(param(p, "id")?, handle(), state())),
+        "set_todo_completed" => reply(commands::set_todo_completed(
+            param(p, "id")?,
+            param(p, "completed")?,
+            handle(),
+            state(),
+        )),
+        "set_todo_starred" => reply(commands::set_todo_starred(
+            param(p, "id")?,
+            param(p, "starred")?,
+            handle(),
+            state(),
+        )),
+        "set_todo_priority" => reply(commands::set_todo_priority(
+            param(p, "id")?,
+            param(p, "priority")?,
+            handle(),
+            state(),
+        )),
+        "set_todo_label" => reply(commands::set_todo_label(
+            param(p, "id")?,
+            param(p, "labelId")?,
+            handle(),
+            state(),
+        )),
+        "set_todo_reminder" => reply(commands::set_todo_reminder(
+            param(p, "id")?,
+            param(p, "reminderAt")?,
+            handle(),
+            state(),
+        )),
+        "set_todo_start" => reply(commands::set_todo_start(
+            param(p, "id")?,
+            param(p, "startAt")?,
+            handle(),
+            state(),
+        )),
+        "move_todo_to_list" => reply(commands::move_todo_to_list(
+            param(p, "id")?,
+            param(p, "listId")?,
+            handle(),
+            state(),
+        )),
+        "reorder_todos" => reply(commands::reorder_todos(
+            param(p, "listId")?,
+            param(p, "parentId")?,
+            param(p, "completed")?,
+            param(p, "orderedIds")?,
+            handle(),
+            state(),
+        )),
+        "delete_todo" => reply(commands::delete_todo(param(p, "id")?, handle(), state())),
+        "clear_completed_in_list" => reply(commands::clear_completed_in_list(
+            param(p, "listId")?,
+            handle(),
+            state(),
+        )),
+        "clear_history" => reply(commands::clear_history(handle(), state())),
+        "create_list" => reply(commands::create_list(param(p, "name")?, handle(), state())),
+        "rename_list" => reply(commands::rename_list(
+            param(p, "id")?,
+            param(p, "name")?,
+            handle(),
+            state(),
+        )),
+        "set_list_icon" => reply(commands::set_list_icon(
+            param(p, "id")?,
+            param(p, "icon")?,
+            handle(),
+            state(),
+        )),
+        "set_list_notification_sound" => reply(commands::set_list_notification_sound(
+            param(p, "id")?,
+            param(p, "sound")?,
+            handle(),
+            state(),
+        )),
+        "set_active_list" => reply(commands::set_active_list(
+            param(p, "id")?,
+            handle(),
+            state(),
+        )),
+        "update_settings" => reply(commands::update_settings(
+            param(p, "settings")?,
+            handle(),
+            state(),
+        )),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {method}"),
+        )),
+    }
+}
+
+/// Synchronous client used by the command-line client.
+pub struct IpcClient {
+    reader: BufReader<interprocess::local_socket::RecvHalf>,
+    writer: interprocess::local_socket::SendHalf,
+    next_id: u64,
+}
+
+impl IpcClient {
+    pub fn connect(data_dir: &Path) -> Result<Self, String> {
+        let stream = socket_name(data_dir)
+            .and_then(LocalSocketStream::connect)
+            .map_err(|error| format!("failed to connect to BlinkDo: {error}"))?;
+        let (reader, writer) = stream.split();
+
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// Sends a request and waits for its response, skipping notifications.
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.writer, "{request}")
+            .and_then(|_| self.writer.flush())
+            .map_err(|error| format!("failed to send IPC request: {error}"))?;
+
+        loop {
+            let mut line = String::new();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|error| format!("failed to read IPC response: {error}"))?;
+            if read == 0 {
+                return Err("BlinkDo closed the connection".to_string());
+            }
+
+            let response = serde_json::from_str::<Value>(&line)
+                .map_err(|error| format!("invalid IPC response: {error}"))?;
+            if response.get("id") != Some(&json!(id)) {
+                continue;
+            }
+
+            if let Some(error) = response.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error");
+                return Err(message.to_string());
+            }
+
+            return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+}
@@ -1,11 +1,26 @@
 mod accessibility;
+mod archive;
 mod changelog;
+pub mod cli;
 mod commands;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod deep_link;
+mod events;
+mod formats;
+mod hooks;
+mod http_api;
+mod i18n;
+mod instance;
+mod ipc;
+mod merge;
+pub mod mcp;
 mod reminder;
 mod shortcuts;
 mod storage;
 mod tray;
 mod updater;
+mod webhooks;
 mod window;
 
 use tauri::Manager;
@@ -14,6 +29,13 @@ use tauri_plugin_autostart::ManagerExt;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must be registered first so that a second launch exits before
+        // setting anything up.
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            let forwarded = args.get(1..).unwrap_or_default();
+            instance::handle_forwarded_args(app, forwarded);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(
             tauri_plugin_log::Builder::default()
                 .level(log::LevelFilter::Info)
@@ -40,9 +62,39 @@ pub fn run() {
 
             let app_handle = app.handle().clone();
 
+            let instance_lock = storage::acquire_app_instance_lock(&app_handle)
+                .map_err(std::io::Error::other)?;
+            app.manage(instance_lock);
+
             let data = storage::load_or_create(&app_handle).map_err(std::io::Error::other)?;
+            app.manage(events::EventTracker::new(&data));
             app.manage(storage::AppState::new(data));
 
+            if let Err(error) = ipc::start_server(app_handle.clone()) {
+                log::error!("failed to start IPC server: {error}");
+            }
+
+            #[cfg(target_os = "linux")]
+            {
+                app.manage(dbus::DbusService::default());
+                if let Err(error) = dbus::start_service(&app_handle) {
+                    log::warn!("failed to start D-Bus service: {error}");
+                }
+            }
+
+            app.manage(http_api::HttpApiServer::default());
+            let http_api_settings = app_handle
+                .state::<storage::AppState>()
+                .snapshot()
+                .settings
+                .http_api;
+            http_api::apply_settings(&app_handle, &http_api_settings);
+
+            app.manage(webhooks::WebhookQueue::default());
+            webhooks::start_worker(app_handle.clone());
+            app.manage(hooks::HookQueue::default());
+            hooks::start_workers(&app_handle);
+
             tray::create_tray(&app_handle)?;
             let current_shortcut = app_handle
                 .state::<storage::AppState>()
@@ -52,16 +104,16 @@ pub fn run() {
             if let Err(error) = shortcuts::register(&app_handle, &current_shortcut) {
                 log::error!("failed to register saved shortcut {current_shortcut}: {error}");
                 shortcuts::register(&app_handle, storage::DEFAULT_GLOBAL_SHORTCUT)?;
+                let state = app_handle.state::<storage::AppState>();
                 {
-                    let state = app_handle.state::<storage::AppState>();
                     let mut guard = state
                         .data
                         .lock()
                         .map_err(|_| std::io::Error::other("failed to lock todo state"))?;
                     guard.settings.global_shortcut = storage::DEFAULT_GLOBAL_SHORTCUT.to_string();
-                    if let Err(persist_error) = storage::persist(&app_handle, &guard) {
-                        log::error!("failed to persist fallback shortcut: {persist_error}");
-                    }
+                }
+                if let Err(persist_error) = commands::persist_state(&app_handle, &state) {
+                    log::error!("failed to persist fallback shortcut: {persist_error}");
                 }
             }
 
@@ -85,10 +137,29 @@ pub fn run() {
                 }
             }
 
+            deep_link::init(&app_handle);
+
+            let launch_args = std::env::args().skip(1).collect::<Vec<_>>();
+            instance::handle_startup_args(&app_handle, &launch_args);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::load_state,
+            commands::load_state_since,
+            formats::ical::export_ical,
+            formats::ical::import_ical,
+            formats::markdown::export_markdown,
+            formats::markdown::import_markdown,
+            formats::migration::import_tasks,
+            formats::opml::export_opml,
+            formats::opml::import_opml,
+            formats::paste::paste_todos,
+            formats::report::export_todos,
+            formats::taskwarrior::export_taskwarrior,
+            formats::taskwarrior::import_taskwarrior,
+            formats::todotxt::export_todotxt,
+            formats::todotxt::import_todotxt,
             commands::add_todo,
             commands::create_todo,
             commands::create_list,
@@ -100,6 +171,7 @@ pub fn run() {
             commands::set_todo_label,
             commands::rename_list,
             commands::set_list_icon,
+            commands::set_list_notification_sound,
             commands::set_active_list,
             commands::move_todo_to_list,
             commands::clear_completed_in_list,
@@ -110,15 +182,22 @@ pub fn run() {
             commands::set_global_shortcut,
             commands::set_autostart_enabled,
             commands::set_todo_reminder,
+            commands::set_todo_start,
+            commands::query_todos,
             commands::hide_overlay,
             commands::get_app_version,
             commands::get_data_file_path,
+            archive::export_archive,
+            archive::import_archive,
             commands::open_data_file,
             commands::get_log_file_path,
             commands::open_log_file,
             commands::reset_all_data,
             commands::set_window_width,
             commands::set_overlay_visor_effect,
+            reminder::acknowledge_reminder,
+            reminder::preview_daily_summary,
+            webhooks::get_webhook_deliveries,
             changelog::get_changelog,
             updater::check_for_update,
             updater::install_update,
@@ -2,5 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if app_lib::cli::is_cli_invocation(&args) {
+        app_lib::cli::attach_console();
+        std::process::exit(app_lib::cli::run(args));
+    }
+    if app_lib::mcp::is_mcp_invocation(&args) {
+        std::process::exit(app_lib::mcp::run());
+    }
+
     app_lib::run();
 }
@@ -0,0 +1,563 @@
+//! `blinkdo --mcp`: Model Context Protocol server on stdin/stdout for local
+//! assistants. It exposes the todo tools below and one resource per todo
+//! list, and reads and writes through the same store as the command-line
+//! client (directly, or through the running app's IPC socket).
+
+use std::io::{self, BufRead, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::cli::{changed_ids, find_todo, list_summaries, Change, Store};
+use crate::storage::{
+    classify_availability, now_millis, ordered_todos, parse_date_time, resolve_list_id,
+    resolve_todo_id, AppData, Todo, TodoAvailability,
+};
+
+pub const FLAG: &str = "--mcp";
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+const LIST_URI_PREFIX: &str = "blinkdo://lists/";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Whether the process arguments (without the program name) select the MCP
+/// server.
+pub fn is_mcp_invocation(args: &[String]) -> bool {
+    args.first().is_some_and(|first| first == FLAG)
+}
+
+/// Serves requests until stdin is closed and returns the process exit code.
+pub fn run() -> i32 {
+    let store = match Store::open() {
+        Ok(store) => store,
+        Err(error) => {
+            eprintln!("blinkdo: {error}");
+            return 1;
+        }
+    };
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(response) = handle_line(&store, &line) else {
+            continue;
+        };
+        if writeln!(stdout, "{response}")
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+
+    0
+}
+
+/// Where the server reads and writes todos.
+trait Source {
+    fn read(&self) -> Result<AppData, String>;
+
+    /// Applies the planned change, returning the data before and after.
+    fn update(
+        &self,
+        plan: impl FnOnce(&AppData) -> Result<Change, String>,
+    ) -> Result<(AppData, AppData), String>;
+}
+
+impl Source for Store {
+    fn read(&self) -> Result<AppData, String> {
+        Store::read(self)
+    }
+
+    fn update(
+        &self,
+        plan: impl FnOnce(&AppData) -> Result<Change, String>,
+    ) -> Result<(AppData, AppData), String> {
+        Store::update(self, plan)
+    }
+}
+
+fn handle_line(store: &impl Source, line: &str) -> Option<Value> {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(error) => return Some(error_response(Value::Null, PARSE_ERROR, error.to_string())),
+    };
+
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            request.get("id").cloned().unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "missing method".to_string(),
+        ));
+    };
+    // Notifications (`notifications/initialized`, ...) get no response.
+    let id = request.get("id").cloned()?;
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    Some(match dispatch(store, method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn dispatch(store: &impl Source, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    match method {
+        "initialize" => {
+            let requested = params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .filter(|version| PROTOCOL_VERSIONS.contains(version))
+                .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+            Ok(json!({
+                "protocolVersion": requested,
+                "capabilities": { "tools": {}, "resources": {} },
+                "serverInfo": { "name": "blinkdo", "version": env!("CARGO_PKG_VERSION") },
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let call = decode::<ToolCall>(params)?;
+            // Tool failures are results the model can read, not protocol errors.
+            Ok(match call_tool(store, &call.name, call.arguments) {
+                Ok(output) => json!({
+                    "content": [{ "type": "text", "text": pretty(&output) }],
+                    "isError": false,
+                }),
+                Err(message) => json!({
+                    "content": [{ "type": "text", "text": message }],
+                    "isError": true,
+                }),
+            })
+        }
+        "resources/list" => {
+            let data = store.read().map_err(internal)?;
+            let resources = list_summaries(&data)
+                .into_iter()
+                .map(|list| {
+                    json!({
+                        "uri": format!("{LIST_URI_PREFIX}{}", list.id),
+                        "name": list.name,
+                        "description": format!("{} open, {} completed", list.open, list.completed),
+                        "mimeType": "application/json",
+                    })
+                })
+                .collect::<Vec<_>>();
+            Ok(json!({ "resources": resources }))
+        }
+        "resources/read" => {
+            let uri = decode::<ResourceRead>(params)?.uri;
+            let data = store.read().map_err(internal)?;
+            let list_id = uri
+                .strip_prefix(LIST_URI_PREFIX)
+                .filter(|id| data.settings.lists.iter().any(|list| list.id == *id))
+                .ok_or_else(|| (INVALID_PARAMS, format!("unknown resource {uri}")))?;
+
+            Ok(json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": pretty(&list_document(&data, list_id)),
+                }],
+            }))
+        }
+        other => Err((METHOD_NOT_FOUND, format!("unknown method {other}"))),
+    }
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct ResourceRead {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListTodosArgs {
+    list: Option<String>,
+    #[serde(default)]
+    include_completed: bool,
+}
+
+#[derive(Deserialize)]
+struct AddTodoArgs {
+    title: String,
+    list: Option<String>,
+    details: Option<String>,
+    parent: Option<String>,
+    remind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompleteTodoArgs {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchTodosArgs {
+    query: String,
+    #[serde(default)]
+    include_completed: bool,
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_todos",
+            "description": "List todos in display order. Without includeCompleted only todos that are open and not deferred are returned.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "list": { "type": "string", "description": "List id or name" },
+                    "includeCompleted": { "type": "boolean" },
+                },
+            },
+        },
+        {
+            "name": "add_todo",
+            "description": "Create a todo and return it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "list": { "type": "string", "description": "List id or name, defaults to the active list" },
+                    "details": { "type": "string" },
+                    "parent": { "type": "string", "description": "Id of the parent todo" },
+                    "remind": { "type": "string", "description": "Reminder as YYYY-MM-DD HH:MM or RFC 3339" },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "complete_todo",
+            "description": "Complete a todo and its subtasks. Ids may be abbreviated to a unique prefix.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"],
+            },
+        },
+        {
+            "name": "search_todos",
+            "description": "Search todo titles and details, case-insensitively.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "includeCompleted": { "type": "boolean" },
+                },
+                "required": ["query"],
+            },
+        },
+    ])
+}
+
+fn call_tool(store: &impl Source, name: &str, arguments: Value) -> Result<Value, String> {
+    match name {
+        "list_todos" => {
+            let args = decode_arguments::<ListTodosArgs>(arguments)?;
+            let data = store.read()?;
+            let list_id = args
+                .list
+                .as_deref()
+                .map(|list| resolve_list_id(&data, list))
+                .transpose()?;
+            let availability = classify_availability(&data.todos, now_millis());
+
+            let todos = ordered_todos(&data)
+                .into_iter()
+                .map(|(todo, _)| todo)
+                .filter(|todo| {
+                    list_id
+                        .as_deref()
+                        .map_or(true, |list_id| todo.list_id.as_deref() == Some(list_id))
+                })
+                .filter(|todo| {
+                    args.include_completed
+                        || availability.get(&todo.id) == Some(&TodoAvailability::Available)
+                })
+                .collect::<Vec<_>>();
+            to_value(&todos)
+        }
+        "add_todo" => {
+            let args = decode_arguments::<AddTodoArgs>(arguments)?;
+            let reminder_at = args
+                .remind
+                .as_deref()
+                .map(|value| parse_date_time(value).ok_or_else(|| format!("invalid date {value}")))
+                .transpose()?;
+
+            let (before, after) = store.update(|data| {
+                Ok(Change::Add {
+                    title: args.title,
+                    details: args.details,
+                    reminder_at,
+                    parent_id: args
+                        .parent
+                        .as_deref()
+                        .map(|parent| resolve_todo_id(data, parent))
+                        .transpose()?,
+                    list_id: args
+                        .list
+                        .as_deref()
+                        .map(|list| resolve_list_id(data, list))
+                        .transpose()?,
+                })
+            })?;
+
+            let added = changed_ids(&before, &after, |previous, _| previous.is_none());
+            let id = added
+                .first()
+                .ok_or_else(|| "the todo was not created".to_string())?;
+            to_value(find_todo(&after, id))
+        }
+        "complete_todo" => {
+            let args = decode_arguments::<CompleteTodoArgs>(arguments)?;
+            let (before, after) = store.update(|data| {
+                Ok(Change::Complete {
+                    id: resolve_todo_id(data, &args.id)?,
+                })
+            })?;
+
+            let completed = changed_ids(&before, &after, |previous, todo| {
+                previous.is_some_and(|previous| previous.completed_at != todo.completed_at)
+            });
+            Ok(json!({ "completed": completed }))
+        }
+        "search_todos" => {
+            let args = decode_arguments::<SearchTodosArgs>(arguments)?;
+            let data = store.read()?;
+            let needle = args.query.to_lowercase();
+
+            let todos = ordered_todos(&data)
+                .into_iter()
+                .map(|(todo, _)| todo)
+                .filter(|todo| args.include_completed || todo.completed_at.is_none())
+                .filter(|todo| {
+                    todo.title.to_lowercase().contains(&needle)
+                        || todo
+                            .details
+                            .as_deref()
+                            .is_some_and(|details| details.to_lowercase().contains(&needle))
+                })
+                .collect::<Vec<_>>();
+            to_value(&todos)
+        }
+        other => Err(format!("unknown tool {other}")),
+    }
+}
+
+/// A list with its todos in display order, as served by `resources/read`.
+fn list_document(data: &AppData, list_id: &str) -> Value {
+    let summary = list_summaries(data)
+        .into_iter()
+        .find(|list| list.id == list_id);
+    let todos = ordered_todos(data)
+        .into_iter()
+        .map(|(todo, _)| todo)
+        .filter(|todo| todo.list_id.as_deref() == Some(list_id))
+        .collect::<Vec<&Todo>>();
+
+    json!({ "list": summary, "todos": todos })
+}
+
+fn decode<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|error| (INVALID_PARAMS, error.to_string()))
+}
+
+fn decode_arguments<T: DeserializeOwned>(arguments: Value) -> Result<T, String> {
+    let arguments = if arguments.is_null() {
+        json!({})
+    } else {
+        arguments
+    };
+    serde_json::from_value(arguments).map_err(|error| format!("invalid arguments: {error}"))
+}
+
+fn to_value(value: &impl serde::Serialize) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|error| format!("failed to serialize result: {error}"))
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn internal(message: String) -> (i64, String) {
+    (INTERNAL_ERROR, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    impl Source for RefCell<AppData> {
+        fn read(&self) -> Result<AppData, String> {
+            Ok(self.borrow().clone())
+        }
+
+        fn update(
+            &self,
+            plan: impl FnOnce(&AppData) -> Result<Change, String>,
+        ) -> Result<(AppData, AppData), String> {
+            let before = self.read()?;
+            let mut after = before.clone();
+            plan(&before)?.apply(&mut after)?;
+            *self.borrow_mut() = after.clone();
+            Ok((before, after))
+        }
+    }
+
+    fn request(store: &RefCell<AppData>, method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        handle_line(store, &line.to_string()).expect("a response")
+    }
+
+    fn tool(store: &RefCell<AppData>, name: &str, arguments: Value) -> (bool, String) {
+        let response = request(
+            store,
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        );
+        let result = &response["result"];
+        (
+            result["isError"].as_bool().expect("isError"),
+            result["content"][0]["text"]
+                .as_str()
+                .expect("text")
+                .to_string(),
+        )
+    }
+
+    #[test]
+    fn answers_protocol_requests() {
+        let store = RefCell::new(AppData::default());
+
+        let response = handle_line(&store, "{not json").expect("a response");
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = handle_line(&store, r#"{"jsonrpc":"2.0","id":7}"#).expect("a response");
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        assert!(handle_line(
+            &store,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#
+        )
+        .is_none());
+
+        let response = request(
+            &store,
+            "initialize",
+            json!({ "protocolVersion": "2025-03-26" }),
+        );
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        let response = request(
+            &store,
+            "initialize",
+            json!({ "protocolVersion": "1999-01-01" }),
+        );
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        let response = request(&store, "todos/sync", Value::Null);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = request(&store, "tools/call", json!({ "arguments": {} }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = request(
+            &store,
+            "resources/read",
+            json!({ "uri": "blinkdo://lists/missing" }),
+        );
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let response = request(&store, "resources/read", json!({}));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn reports_tool_failures_as_results() {
+        let store = RefCell::new(AppData::default());
+
+        let (is_error, text) = tool(&store, "add_todo", json!({ "details": "no title" }));
+        assert!(is_error);
+        assert!(text.starts_with("invalid arguments:"), "{text}");
+
+        let (is_error, text) = tool(
+            &store,
+            "add_todo",
+            json!({ "title": "Call", "remind": "tomorrow" }),
+        );
+        assert!(is_error);
+        assert_eq!(text, "invalid date tomorrow");
+
+        let (is_error, text) = tool(&store, "complete_todo", json!({ "id": "missing" }));
+        assert!(is_error, "{text}");
+        let (is_error, text) = tool(&store, "archive_todos", Value::Null);
+        assert!(is_error);
+        assert_eq!(text, "unknown tool archive_todos");
+        assert!(store.borrow().todos.is_empty());
+
+        let (is_error, text) = tool(&store, "add_todo", json!({ "title": "Buy milk" }));
+        assert!(!is_error, "{text}");
+        let added = serde_json::from_str::<Todo>(&text).expect("a todo");
+        assert_eq!(added.title, "Buy milk");
+
+        let (is_error, text) = tool(&store, "search_todos", json!({ "query": "MILK" }));
+        assert!(!is_error);
+        let found = serde_json::from_str::<Vec<Todo>>(&text).expect("todos");
+        assert_eq!(found.len(), 1);
+
+        let (is_error, text) = tool(&store, "complete_todo", json!({ "id": added.id }));
+        assert!(!is_error);
+        assert_eq!(
+            serde_json::from_str::<Value>(&text).expect("JSON"),
+            json!({ "completed": [added.id] })
+        );
+        let (_, text) = tool(&store, "list_todos", Value::Null);
+        assert_eq!(text, "[]");
+
+        let list_uri = format!("{LIST_URI_PREFIX}{}", store.borrow().settings.lists[0].id);
+        let response = request(&store, "resources/read", json!({ "uri": list_uri }));
+        let document = serde_json::from_str::<Value>(
+            response["result"]["contents"][0]["text"]
+                .as_str()
+                .expect("text"),
+        )
+        .expect("JSON");
+        assert_eq!(document["todos"][0]["title"], "Buy milk");
+    }
+}
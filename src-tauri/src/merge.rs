@@ -0,0 +1,372 @@
+//! Merges another `AppData` into the current one by id. Lists, labels and
+//! todos missing locally are added; records present on both sides with a
+//! different content are conflicts, settled by a `MergeStrategy` and listed
+//! in the report. Settings other than lists and labels stay local.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::storage::{AppData, Todo, TodoLabel, TodoList};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    KeepLocal,
+    #[default]
+    KeepIncoming,
+    /// The todo edited last, the local one on a tie. Lists and labels have no
+    /// edit time and stay local.
+    NewestWins,
+    /// Keeps both, the incoming record under a new id.
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordKind {
+    List,
+    Label,
+    Todo,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Resolution {
+    KeptLocal,
+    TookIncoming,
+    Duplicated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflict {
+    pub kind: RecordKind,
+    pub id: String,
+    /// Name of the list or label, title of the todo.
+    pub local_name: String,
+    pub incoming_name: String,
+    /// JSON names of the fields that differ.
+    pub fields: Vec<String>,
+    /// Last edit on each side, for todos.
+    pub local_edited_at: Option<i64>,
+    pub incoming_edited_at: Option<i64>,
+    pub resolution: Resolution,
+    /// Id given to the incoming copy with `Duplicate`.
+    pub duplicate_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub lists_added: usize,
+    pub lists_updated: usize,
+    pub labels_added: usize,
+    pub labels_updated: usize,
+    pub todos_added: usize,
+    pub todos_updated: usize,
+    pub conflicts: Vec<Conflict>,
+}
+
+trait Record: Serialize {
+    const KIND: RecordKind;
+
+    fn id(&self) -> &str;
+    fn set_id(&mut self, id: String);
+    fn name(&self) -> &str;
+
+    fn edited_at(&self) -> Option<i64> {
+        None
+    }
+}
+
+impl Record for TodoList {
+    const KIND: RecordKind = RecordKind::List;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Record for TodoLabel {
+    const KIND: RecordKind = RecordKind::Label;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Record for Todo {
+    const KIND: RecordKind = RecordKind::Todo;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn name(&self) -> &str {
+        &self.title
+    }
+
+    /// Todos saved before edit times were recorded fall back to their
+    /// creation and completion.
+    fn edited_at(&self) -> Option<i64> {
+        [Some(self.created_at), self.completed_at, self.updated_at]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+}
+
+/// What happens to one incoming record.
+enum Plan {
+    Skip,
+    Add,
+    Replace(usize),
+}
+
+/// Merges `incoming` into `data`. The caller normalizes the result.
+pub fn merge(data: &mut AppData, incoming: AppData, strategy: MergeStrategy) -> MergeReport {
+    let mut report = MergeReport::default();
+
+    let (added, updated, list_ids) = merge_records(
+        &mut data.settings.lists,
+        incoming.settings.lists,
+        strategy,
+        &mut report.conflicts,
+        |_, _| {},
+    );
+    report.lists_added = added;
+    report.lists_updated = updated;
+
+    let (added, updated, label_ids) = merge_records(
+        &mut data.settings.labels,
+        incoming.settings.labels,
+        strategy,
+        &mut report.conflicts,
+        |_, _| {},
+    );
+    report.labels_added = added;
+    report.labels_updated = updated;
+
+    let remap = |ids: &HashMap<String, String>, id: &mut Option<String>| {
+        if let Some(new_id) = id.as_deref().and_then(|id| ids.get(id)) {
+            *id = Some(new_id.clone());
+        }
+    };
+    let (added, updated, _) = merge_records(
+        &mut data.todos,
+        incoming.todos,
+        strategy,
+        &mut report.conflicts,
+        |todo, todo_ids| {
+            remap(&list_ids, &mut todo.list_id);
+            remap(&label_ids, &mut todo.label_id);
+            remap(todo_ids, &mut todo.parent_id);
+        },
+    );
+    report.todos_added = added;
+    report.todos_updated = updated;
+
+    report
+}
+
+/// Merges `incoming` into `local` and returns how many records were added
+/// and updated, and the new ids of duplicated records. `relink` updates the
+/// references of every record taken from `incoming`, given those new ids.
+fn merge_records<T: Record>(
+    local: &mut Vec<T>,
+    incoming: Vec<T>,
+    strategy: MergeStrategy,
+    conflicts: &mut Vec<Conflict>,
+    relink: impl Fn(&mut T, &HashMap<String, String>),
+) -> (usize, usize, HashMap<String, String>) {
+    let positions = local
+        .iter()
+        .enumerate()
+        .map(|(index, record)| (record.id().to_string(), index))
+        .collect::<HashMap<_, _>>();
+    let mut plans = Vec::with_capacity(incoming.len());
+    let mut new_ids = HashMap::new();
+
+    for record in &incoming {
+        let Some(&index) = positions.get(record.id()) else {
+            plans.push(Plan::Add);
+            continue;
+        };
+        let fields = differing_fields(&local[index], record);
+        if fields.is_empty() {
+            plans.push(Plan::Skip);
+            continue;
+        }
+
+        let existing = &local[index];
+        let resolution = match strategy {
+            MergeStrategy::KeepLocal => Resolution::KeptLocal,
+            MergeStrategy::KeepIncoming => Resolution::TookIncoming,
+            MergeStrategy::NewestWins if record.edited_at() > existing.edited_at() => {
+                Resolution::TookIncoming
+            }
+            MergeStrategy::NewestWins => Resolution::KeptLocal,
+            MergeStrategy::Duplicate => Resolution::Duplicated,
+        };
+        let duplicate_id = (resolution == Resolution::Duplicated).then(|| {
+            let id = Uuid::new_v4().to_string();
+            new_ids.insert(record.id().to_string(), id.clone());
+            id
+        });
+
+        conflicts.push(Conflict {
+            kind: T::KIND,
+            id: record.id().to_string(),
+            local_name: existing.name().to_string(),
+            incoming_name: record.name().to_string(),
+            fields,
+            local_edited_at: existing.edited_at(),
+            incoming_edited_at: record.edited_at(),
+            resolution,
+            duplicate_id,
+        });
+        plans.push(match resolution {
+            Resolution::KeptLocal => Plan::Skip,
+            Resolution::TookIncoming => Plan::Replace(index),
+            Resolution::Duplicated => Plan::Add,
+        });
+    }
+
+    let (mut added, mut updated) = (0, 0);
+    for (mut record, plan) in incoming.into_iter().zip(plans) {
+        if matches!(plan, Plan::Skip) {
+            continue;
+        }
+        if let Some(new_id) = new_ids.get(record.id()) {
+            record.set_id(new_id.clone());
+        }
+        relink(&mut record, &new_ids);
+
+        match plan {
+            Plan::Replace(index) => {
+                local[index] = record;
+                updated += 1;
+            }
+            _ => {
+                local.push(record);
+                added += 1;
+            }
+        }
+    }
+
+    (added, updated, new_ids)
+}
+
+/// JSON names of the fields whose values differ between `local` and
+/// `incoming`.
+fn differing_fields<T: Serialize>(local: &T, incoming: &T) -> Vec<String> {
+    let (Ok(Value::Object(local)), Ok(Value::Object(incoming))) =
+        (serde_json::to_value(local), serde_json::to_value(incoming))
+    else {
+        return Vec::new();
+    };
+
+    let mut fields = local
+        .iter()
+        .filter(|(key, value)| incoming.get(*key) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+    fields.extend(
+        incoming
+            .keys()
+            .filter(|key| !local.contains_key(*key))
+            .cloned(),
+    );
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: &str, title: &str, updated_at: Option<i64>) -> Todo {
+        Todo {
+            updated_at,
+            ..Todo::for_test(id, title)
+        }
+    }
+
+    fn data(todos: Vec<Todo>) -> AppData {
+        AppData {
+            todos,
+            ..AppData::default()
+        }
+    }
+
+    #[test]
+    fn settles_conflicts_with_each_strategy() {
+        let local = data(vec![
+            todo("a", "Local edit", Some(3_000)),
+            todo("b", "Same", None),
+            todo("c", "Local newer", Some(9_000)),
+        ]);
+        let mut child = todo("d", "Incoming child", None);
+        child.parent_id = Some("a".to_string());
+        let incoming = data(vec![
+            todo("a", "Incoming edit", Some(5_000)),
+            todo("b", "Same", None),
+            todo("c", "Incoming older", Some(2_000)),
+            child,
+        ]);
+
+        let mut merged = local.clone();
+        let report = merge(&mut merged, incoming.clone(), MergeStrategy::NewestWins);
+        assert_eq!(report.todos_added, 1);
+        assert_eq!(report.todos_updated, 1);
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(report.conflicts[0].fields, vec!["title", "updatedAt"]);
+        assert_eq!(report.conflicts[0].resolution, Resolution::TookIncoming);
+        assert_eq!(report.conflicts[1].resolution, Resolution::KeptLocal);
+        assert_eq!(merged.todos[0].title, "Incoming edit");
+        assert_eq!(merged.todos[2].title, "Local newer");
+
+        let mut merged = local.clone();
+        let report = merge(&mut merged, incoming.clone(), MergeStrategy::KeepLocal);
+        assert_eq!(report.todos_updated, 0);
+        assert_eq!(merged.todos[0].title, "Local edit");
+
+        let mut merged = local;
+        let report = merge(&mut merged, incoming, MergeStrategy::Duplicate);
+        let copy_id = report.conflicts[0]
+            .duplicate_id
+            .clone()
+            .expect("incoming copy");
+        assert_eq!(report.todos_added, 3);
+        assert_eq!(merged.todos.len(), 6);
+        assert_eq!(merged.todos[0].title, "Local edit");
+        let child = merged
+            .todos
+            .iter()
+            .find(|todo| todo.id == "d")
+            .expect("added");
+        assert_eq!(child.parent_id.as_deref(), Some(copy_id.as_str()));
+    }
+}
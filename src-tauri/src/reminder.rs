@@ -1,29 +1,74 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::thread;
 use std::time::Duration;
 
-use tauri::{AppHandle, Manager};
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_notification::NotificationExt;
 
-use crate::storage::{now_millis, AppState, Todo};
+use crate::commands::persist_state;
+use crate::events;
+use crate::i18n;
+use crate::storage::{
+    now_millis, AppData, AppState, ReminderDelivery, ReminderEscalationSettings, Settings, Todo,
+    TodoPriority,
+};
+use crate::window;
 
 const POLL_INTERVAL_SECONDS: u64 = 10;
+// A summary missed while the machine was asleep or the app closed is still
+// sent if we wake up within this window after the scheduled time.
+const DAILY_SUMMARY_CATCH_UP_MINUTES: i64 = 60;
+const DAILY_SUMMARY_MAX_REMINDERS: usize = 3;
+// High priority reminders are shown at most twice: once when due, once more
+// if the todo is still open after `high_renotify_minutes`.
+const HIGH_PRIORITY_MAX_NOTIFICATIONS: u32 = 2;
 
 pub fn start_scheduler(app: AppHandle) {
+    let started_at = now_millis();
+
     thread::spawn(move || loop {
         if let Err(error) = check_due_reminders(&app) {
             log::error!("reminder scheduler failed: {error}");
         }
 
+        if let Err(error) = check_available_todos(&app, started_at) {
+            log::error!("deferred todo scheduler failed: {error}");
+        }
+
+        if let Err(error) = check_daily_summary(&app) {
+            log::error!("daily summary scheduler failed: {error}");
+        }
+
         thread::sleep(Duration::from_secs(POLL_INTERVAL_SECONDS));
     });
 }
 
+/// Stops an escalating reminder from repeating until its reminder is changed.
+#[tauri::command]
+pub fn acknowledge_reminder(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let mut notified = state
+        .notified_todos
+        .lock()
+        .map_err(|_| String::from("failed to lock reminder state"))?;
+
+    if let Some(delivery) = notified.get_mut(&id) {
+        delivery.acknowledged = true;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn preview_daily_summary(state: State<'_, AppState>) -> String {
+    build_daily_summary(&state.snapshot(), Local::now())
+}
+
 fn check_due_reminders(app: &AppHandle) -> Result<(), String> {
     let state = app.state::<AppState>();
     let now = now_millis();
 
-    let (active_ids, due_todos): (HashSet<String>, Vec<Todo>) = {
+    let (active_ids, due_todos, settings): (HashSet<String>, Vec<Todo>, Settings) = {
         let guard = state
             .data
             .lock()
@@ -46,17 +91,182 @@ fn check_due_reminders(app: &AppHandle) -> Result<(), String> {
             .cloned()
             .collect::<Vec<_>>();
 
-        (active, due)
+        (active, due, guard.settings.clone())
     };
+    let messages = i18n::messages(&settings.language);
+    let escalation = &settings.reminder_escalation;
+    let play_sound = settings.sound_settings.enabled && settings.sound_settings.on_reminder;
 
     let mut notified = state
         .notified_todos
         .lock()
         .map_err(|_| String::from("failed to lock reminder state"))?;
 
-    notified.retain(|id| active_ids.contains(id));
+    notified.retain(|id, _| active_ids.contains(id));
 
     for todo in due_todos {
+        let previous = notified.get(&todo.id).copied();
+        if !should_notify(&todo, previous.as_ref(), escalation, now) {
+            continue;
+        }
+
+        let list = settings
+            .lists
+            .iter()
+            .find(|list| todo.list_id.as_deref() == Some(list.id.as_str()));
+
+        let mut notification = app
+            .notification()
+            .builder()
+            .title(messages.reminder_title)
+            .body(reminder_body(&todo, &settings, messages));
+
+        if let Some(list) = list {
+            notification = notification.group(list.id.clone());
+        }
+
+        if let Some(sound) = list
+            .and_then(|list| list.notification_sound.clone())
+            .filter(|_| play_sound)
+        {
+            notification = notification.sound(sound);
+        }
+
+        if let Err(error) = notification.show() {
+            log::error!(
+                "failed to display reminder notification for {}: {error}",
+                todo.id
+            );
+            continue;
+        }
+
+        events::publish_reminder(app, &settings, &todo);
+
+        if todo.priority == TodoPriority::Urgent && escalation.urgent_show_overlay {
+            if let Err(error) = window::show_overlay_window(app) {
+                log::error!(
+                    "failed to show overlay for urgent reminder {}: {error}",
+                    todo.id
+                );
+            }
+        }
+
+        notified.insert(
+            todo.id,
+            ReminderDelivery {
+                last_notified_at: now,
+                count: previous.map_or(1, |delivery| delivery.count.saturating_add(1)),
+                acknowledged: false,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Title, then list / label / priority, then the first line of the details.
+fn reminder_body(todo: &Todo, settings: &Settings, messages: &i18n::Messages) -> String {
+    let list_name = settings
+        .lists
+        .iter()
+        .find(|list| todo.list_id.as_deref() == Some(list.id.as_str()))
+        .map(|list| list.name.as_str());
+    let label_name = settings
+        .labels
+        .iter()
+        .find(|label| todo.label_id.as_deref() == Some(label.id.as_str()))
+        .map(|label| label.name.as_str());
+
+    let context = [list_name, label_name, messages.priority(todo.priority)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut lines = vec![todo.title.clone()];
+
+    if !context.is_empty() {
+        lines.push(context.join(" · "));
+    }
+
+    if let Some(first_line) = todo
+        .details
+        .as_deref()
+        .and_then(|details| details.lines().map(str::trim).find(|line| !line.is_empty()))
+    {
+        lines.push(first_line.to_string());
+    }
+
+    lines.join("\n")
+}
+
+fn should_notify(
+    todo: &Todo,
+    previous: Option<&ReminderDelivery>,
+    escalation: &ReminderEscalationSettings,
+    now: i64,
+) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+
+    if previous.acknowledged {
+        return false;
+    }
+
+    let repeat_minutes = match todo.priority {
+        TodoPriority::Urgent => escalation.urgent_repeat_minutes,
+        TodoPriority::High if previous.count < HIGH_PRIORITY_MAX_NOTIFICATIONS => {
+            escalation.high_renotify_minutes
+        }
+        _ => 0,
+    };
+
+    repeat_minutes > 0
+        && now.saturating_sub(previous.last_notified_at) >= i64::from(repeat_minutes) * 60_000
+}
+
+/// Notifies when a deferred todo reaches its `start_at`. Start dates that
+/// passed before the app was launched are not announced.
+fn check_available_todos(app: &AppHandle, started_at: i64) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let now = now_millis();
+
+    let (started_todos, language) = {
+        let guard = state
+            .data
+            .lock()
+            .map_err(|_| String::from("failed to lock todo state"))?;
+
+        if !guard.settings.notify_when_available {
+            return Ok(());
+        }
+
+        let started = guard
+            .todos
+            .iter()
+            .filter(|todo| {
+                todo.completed_at.is_none()
+                    && todo
+                        .start_at
+                        .is_some_and(|start| (started_at..=now).contains(&start))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        (started, guard.settings.language.clone())
+    };
+    let messages = i18n::messages(&language);
+
+    let mut notified = state
+        .available_notified
+        .lock()
+        .map_err(|_| String::from("failed to lock reminder state"))?;
+
+    // Todos deferred again, completed or deleted are forgotten so that a new
+    // start date is announced once more.
+    notified.retain(|id| started_todos.iter().any(|todo| todo.id == *id));
+
+    for todo in started_todos {
         if notified.contains(&todo.id) {
             continue;
         }
@@ -64,12 +274,12 @@ fn check_due_reminders(app: &AppHandle) -> Result<(), String> {
         if let Err(error) = app
             .notification()
             .builder()
-            .title("Rappel tâche")
+            .title(messages.available_title)
             .body(todo.title.clone())
             .show()
         {
             log::error!(
-                "failed to display reminder notification for {}: {error}",
+                "failed to display availability notification for {}: {error}",
                 todo.id
             );
             continue;
@@ -80,3 +290,188 @@ fn check_due_reminders(app: &AppHandle) -> Result<(), String> {
 
     Ok(())
 }
+
+fn check_daily_summary(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let now = Local::now();
+
+    let data = {
+        let guard = state
+            .data
+            .lock()
+            .map_err(|_| String::from("failed to lock todo state"))?;
+
+        let settings = &guard.settings.daily_summary;
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        let scheduled = NaiveTime::parse_from_str(&settings.time, "%H:%M")
+            .map_err(|error| format!("invalid daily summary time {}: {error}", settings.time))?;
+        // Compared as date-times, so that a 23:30 summary is still caught up
+        // after midnight.
+        let Some(occurrence) = last_occurrence(now, scheduled) else {
+            return Ok(());
+        };
+        if now.signed_duration_since(occurrence).num_minutes() >= DAILY_SUMMARY_CATCH_UP_MINUTES
+            || settings
+                .last_sent_at
+                .is_some_and(|sent_at| sent_at >= occurrence.timestamp_millis())
+        {
+            return Ok(());
+        }
+
+        guard.clone()
+    };
+
+    app.notification()
+        .builder()
+        .title(i18n::messages(&data.settings.language).daily_summary_title)
+        .body(build_daily_summary(&data, now))
+        .show()
+        .map_err(|error| format!("failed to display daily summary notification: {error}"))?;
+
+    {
+        let mut guard = state
+            .data
+            .lock()
+            .map_err(|_| String::from("failed to lock todo state"))?;
+        guard.settings.daily_summary.last_sent_at = Some(now.timestamp_millis());
+    }
+    persist_state(app, &state)?;
+    Ok(())
+}
+
+/// The latest time of day `scheduled` at or before `now`, today or yesterday.
+fn last_occurrence(now: DateTime<Local>, scheduled: NaiveTime) -> Option<DateTime<Local>> {
+    let at = |date: chrono::NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_time(scheduled))
+            .earliest()
+    };
+
+    let today = now.date_naive();
+    match at(today) {
+        Some(occurrence) if occurrence <= now => Some(occurrence),
+        _ => today.pred_opt().and_then(at),
+    }
+}
+
+fn local_day_bounds(now: DateTime<Local>) -> (i64, i64) {
+    let start_of = |date: chrono::NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+            .map(|start| start.timestamp_millis())
+    };
+
+    let today = now.date_naive();
+    let start = start_of(today).unwrap_or_else(|| now.timestamp_millis());
+    let end = today.succ_opt().and_then(start_of).unwrap_or(i64::MAX);
+
+    (start, end)
+}
+
+fn format_local_time(millis: i64) -> String {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+pub fn build_daily_summary(data: &AppData, now: DateTime<Local>) -> String {
+    let messages = i18n::messages(&data.settings.language);
+    let (start_of_day, end_of_day) = local_day_bounds(now);
+    let open_todos: Vec<&Todo> = data
+        .todos
+        .iter()
+        .filter(|todo| todo.completed_at.is_none())
+        .collect();
+
+    if open_todos.is_empty() {
+        return messages.daily_summary_empty.to_string();
+    }
+
+    let mut open_by_list: HashMap<&str, usize> = HashMap::new();
+    for todo in &open_todos {
+        *open_by_list
+            .entry(todo.list_id.as_deref().unwrap_or_default())
+            .or_default() += 1;
+    }
+
+    let list_counts = data
+        .settings
+        .lists
+        .iter()
+        .filter_map(|list| {
+            open_by_list.get(list.id.as_str()).map(|count| {
+                i18n::fill(
+                    messages.daily_summary_list_count,
+                    &[("name", &list.name), ("count", &count.to_string())],
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let overdue_count = open_todos
+        .iter()
+        .filter(|todo| {
+            todo.reminder_at
+                .is_some_and(|reminder| reminder < start_of_day)
+        })
+        .count();
+    let starred_count = open_todos.iter().filter(|todo| todo.starred).count();
+
+    let mut todays_reminders = open_todos
+        .iter()
+        .filter_map(|todo| {
+            todo.reminder_at
+                .filter(|reminder| (start_of_day..end_of_day).contains(reminder))
+                .map(|reminder| (reminder, todo.title.as_str()))
+        })
+        .collect::<Vec<_>>();
+    todays_reminders.sort_by_key(|(reminder, _)| *reminder);
+
+    let mut lines = vec![i18n::fill(
+        messages.daily_summary_open,
+        &[("count", &open_todos.len().to_string())],
+    )];
+
+    if !list_counts.is_empty() {
+        lines.push(list_counts.join(" · "));
+    }
+
+    if overdue_count > 0 {
+        lines.push(i18n::fill(
+            messages.daily_summary_overdue,
+            &[("count", &overdue_count.to_string())],
+        ));
+    }
+
+    if starred_count > 0 {
+        lines.push(i18n::fill(
+            messages.daily_summary_starred,
+            &[("count", &starred_count.to_string())],
+        ));
+    }
+
+    if !todays_reminders.is_empty() {
+        let mut entries = todays_reminders
+            .iter()
+            .take(DAILY_SUMMARY_MAX_REMINDERS)
+            .map(|(reminder, title)| format!("{} {title}", format_local_time(*reminder)))
+            .collect::<Vec<_>>();
+        if todays_reminders.len() > DAILY_SUMMARY_MAX_REMINDERS {
+            entries.push(format!(
+                "+{}",
+                todays_reminders.len() - DAILY_SUMMARY_MAX_REMINDERS
+            ));
+        }
+        lines.push(i18n::fill(
+            messages.daily_summary_reminders,
+            &[("items", &entries.join(", "))],
+        ));
+    }
+
+    lines.join("\n")
+}
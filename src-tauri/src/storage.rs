@@ -1,14 +1,36 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fs;
-use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use fs4::fs_std::FileExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Manager};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
 
+use crate::i18n;
+
+pub const APP_IDENTIFIER: &str = "com.simon.blinkdo";
+pub const STATE_CHANGED_EVENT: &str = "state-changed";
 pub const STORAGE_FILE_NAME: &str = "todos.json";
+pub const DATA_LOCK_FILE_NAME: &str = "todos.json.lock";
+pub const INSTANCE_LOCK_FILE_NAME: &str = "blinkdo.lock";
 pub const DEFAULT_LIST_ID: &str = "default";
 pub const DEFAULT_GLOBAL_SHORTCUT: &str = "Shift+Space";
+pub const DEFAULT_DAILY_SUMMARY_TIME: &str = "08:00";
+pub const DEFAULT_URGENT_REPEAT_MINUTES: u32 = 5;
+pub const DEFAULT_HIGH_RENOTIFY_MINUTES: u32 = 15;
+pub const DEFAULT_HTTP_API_PORT: u16 = 47_823;
+pub const DEFAULT_SCRIPT_HOOK_TIMEOUT_SECONDS: u32 = 30;
+pub const MAX_SCRIPT_HOOK_TIMEOUT_SECONDS: u32 = 600;
+/// 1973-03-03: smaller integers are not taken as Unix millis.
+const MIN_EPOCH_MILLIS: i64 = 100_000_000_000;
+const INSTANCE_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const INSTANCE_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -40,6 +62,8 @@ pub struct SoundSettings {
     pub on_complete: bool,
     #[serde(default = "default_true")]
     pub on_delete: bool,
+    #[serde(default = "default_true")]
+    pub on_reminder: bool,
 }
 
 impl Default for SoundSettings {
@@ -49,10 +73,147 @@ impl Default for SoundSettings {
             on_create: true,
             on_complete: true,
             on_delete: true,
+            on_reminder: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySummarySettings {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    #[serde(default = "default_daily_summary_time")]
+    pub time: String,
+    /// When the last summary was shown, kept across restarts so that it is
+    /// not shown twice. Set by the scheduler only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sent_at: Option<i64>,
+}
+
+impl Default for DailySummarySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: default_daily_summary_time(),
+            last_sent_at: None,
+        }
+    }
+}
+
+/// How reminders escalate depending on `TodoPriority`. A value of `0` minutes
+/// disables the repeat for that priority.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderEscalationSettings {
+    #[serde(default = "default_urgent_repeat_minutes")]
+    pub urgent_repeat_minutes: u32,
+    #[serde(default = "default_true")]
+    pub urgent_show_overlay: bool,
+    #[serde(default = "default_high_renotify_minutes")]
+    pub high_renotify_minutes: u32,
+}
+
+impl Default for ReminderEscalationSettings {
+    fn default() -> Self {
+        Self {
+            urgent_repeat_minutes: DEFAULT_URGENT_REPEAT_MINUTES,
+            urgent_show_overlay: true,
+            high_renotify_minutes: DEFAULT_HIGH_RENOTIFY_MINUTES,
+        }
+    }
+}
+
+/// Opt-in REST API served on `127.0.0.1:<port>`, authenticated with
+/// `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiSettings {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    #[serde(default = "default_http_api_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_HTTP_API_PORT,
+            token: String::new(),
         }
     }
 }
 
+/// Events that can be forwarded to webhooks.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TodoEventKind {
+    #[serde(alias = "on_create")]
+    Created,
+    #[serde(alias = "on_complete")]
+    Completed,
+    #[serde(alias = "on_delete")]
+    Deleted,
+    #[serde(alias = "on_reminder")]
+    Reminder,
+}
+
+impl TodoEventKind {
+    pub const ALL: [TodoEventKind; 4] = [
+        TodoEventKind::Created,
+        TodoEventKind::Completed,
+        TodoEventKind::Deleted,
+        TodoEventKind::Reminder,
+    ];
+
+    /// Name used in payloads, e.g. `todo.completed`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TodoEventKind::Created => "todo.created",
+            TodoEventKind::Completed => "todo.completed",
+            TodoEventKind::Deleted => "todo.deleted",
+            TodoEventKind::Reminder => "todo.reminder",
+        }
+    }
+}
+
+/// HTTP endpoint receiving a JSON `POST` for each subscribed event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSettings {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(
+        default = "default_webhook_events",
+        deserialize_with = "deserialize_known_events"
+    )]
+    pub events: Vec<TodoEventKind>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Local executable run on an event with the todo as JSON on stdin.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptHookSettings {
+    #[serde(default)]
+    pub id: String,
+    pub event: TodoEventKind,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_script_hook_timeout_seconds")]
+    pub timeout_seconds: u32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ThemeMode {
@@ -88,6 +249,8 @@ pub struct TodoList {
     pub name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_sound: Option<String>,
     pub created_at: i64,
 }
 
@@ -114,6 +277,43 @@ pub struct Todo {
     pub created_at: i64,
     pub completed_at: Option<i64>,
     pub reminder_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<i64>,
+    /// Time of the last edit, `None` until the todo is first changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+#[cfg(test)]
+impl Todo {
+    /// An open root todo of the default list, for tests to adjust with struct
+    /// update syntax.
+    pub fn for_test(id: &str, title: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            details: None,
+            parent_id: None,
+            list_id: Some(DEFAULT_LIST_ID.to_string()),
+            starred: false,
+            priority: TodoPriority::None,
+            label_id: None,
+            sort_index: None,
+            created_at: 1_000,
+            completed_at: None,
+            reminder_at: None,
+            start_at: None,
+            updated_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TodoAvailability {
+    Available,
+    Deferred,
+    Completed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,6 +345,18 @@ pub struct Settings {
     pub language: String,
     #[serde(default = "default_false")]
     pub enable_overlay_blur: bool,
+    #[serde(default)]
+    pub daily_summary: DailySummarySettings,
+    #[serde(default)]
+    pub reminder_escalation: ReminderEscalationSettings,
+    #[serde(default = "default_false")]
+    pub notify_when_available: bool,
+    #[serde(default)]
+    pub http_api: HttpApiSettings,
+    #[serde(default, deserialize_with = "deserialize_valid_entries")]
+    pub webhooks: Vec<WebhookSettings>,
+    #[serde(default, deserialize_with = "deserialize_valid_entries")]
+    pub script_hooks: Vec<ScriptHookSettings>,
     #[serde(default, alias = "listName", alias = "list_name", skip_serializing)]
     pub legacy_list_name: Option<String>,
 }
@@ -165,6 +377,12 @@ impl Default for Settings {
             sound_settings: SoundSettings::default(),
             language: default_language(),
             enable_overlay_blur: false,
+            daily_summary: DailySummarySettings::default(),
+            reminder_escalation: ReminderEscalationSettings::default(),
+            notify_when_available: false,
+            http_api: HttpApiSettings::default(),
+            webhooks: Vec::new(),
+            script_hooks: Vec::new(),
             legacy_list_name: None,
         }
     }
@@ -177,32 +395,227 @@ pub struct AppData {
     pub settings: Settings,
     #[serde(default)]
     pub todos: Vec<Todo>,
+    /// Revision of this copy, sent with command results so that webviews can
+    /// drop older `state-changed` events. Never read nor saved.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
+}
+
+/// Tracks the reminder notifications already shown for a todo so that the
+/// scheduler can apply the escalation policy of its priority.
+#[derive(Debug, Clone, Copy)]
+pub struct ReminderDelivery {
+    pub last_notified_at: i64,
+    pub count: u32,
+    pub acknowledged: bool,
 }
 
 pub struct AppState {
     pub data: Mutex<AppData>,
-    pub notified_todos: Mutex<HashSet<String>>,
+    pub notified_todos: Mutex<HashMap<String, ReminderDelivery>>,
+    pub available_notified: Mutex<HashSet<String>>,
+    /// Incremented on every change to `data`, while holding its lock.
+    pub revision: AtomicU64,
+    /// Revision last written to disk, locked while writing.
+    pub persisted_revision: Mutex<u64>,
 }
 
 impl AppState {
     pub fn new(data: AppData) -> Self {
         Self {
             data: Mutex::new(data),
-            notified_todos: Mutex::new(HashSet::new()),
+            notified_todos: Mutex::new(HashMap::new()),
+            available_notified: Mutex::new(HashSet::new()),
+            revision: AtomicU64::new(0),
+            persisted_revision: Mutex::new(0),
         }
     }
 
+    /// Returns the next revision. Call it with `data` locked so that
+    /// revisions follow the order of the changes.
+    pub fn next_revision(&self) -> u64 {
+        self.revision.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
     pub fn snapshot(&self) -> AppData {
         self.data.lock().expect("todo state lock poisoned").clone()
     }
 }
 
+/// Classifies every todo by availability at `now`. A todo is deferred while its
+/// own `start_at`, or the one of any ancestor, is still in the future.
+pub fn classify_availability(todos: &[Todo], now: i64) -> HashMap<String, TodoAvailability> {
+    let by_id: HashMap<&str, &Todo> = todos.iter().map(|todo| (todo.id.as_str(), todo)).collect();
+
+    todos
+        .iter()
+        .map(|todo| {
+            if todo.completed_at.is_some() {
+                return (todo.id.clone(), TodoAvailability::Completed);
+            }
+
+            let mut visited = HashSet::new();
+            let mut current = Some(todo);
+            let mut deferred = false;
+
+            while let Some(candidate) = current {
+                if !visited.insert(candidate.id.as_str()) {
+                    break;
+                }
+
+                if candidate.start_at.is_some_and(|start_at| start_at > now) {
+                    deferred = true;
+                    break;
+                }
+
+                current = candidate
+                    .parent_id
+                    .as_deref()
+                    .and_then(|parent_id| by_id.get(parent_id).copied());
+            }
+
+            let availability = if deferred {
+                TodoAvailability::Deferred
+            } else {
+                TodoAvailability::Available
+            };
+            (todo.id.clone(), availability)
+        })
+        .collect()
+}
+
+/// Accepts Unix millis, RFC 3339, or a local `YYYY-MM-DD[ HH:MM]`. Used by
+/// the CLI, `blinkdo://` links and the text formats. Integers below
+/// `MIN_EPOCH_MILLIS`, such as a bare year, are rejected rather than read as
+/// dates in 1970.
+pub fn parse_date_time(value: &str) -> Option<i64> {
+    let trimmed = value.trim();
+
+    if let Ok(millis) = trimmed.parse::<i64>() {
+        return (millis >= MIN_EPOCH_MILLIS).then_some(millis);
+    }
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(trimmed) {
+        return Some(date_time.timestamp_millis());
+    }
+
+    let local = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(9, 0, 0))
+        });
+
+    local
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+/// Exact id, or a prefix matching exactly one todo.
+pub fn resolve_todo_id(data: &AppData, input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("empty todo id".to_string());
+    }
+
+    if data.todos.iter().any(|todo| todo.id == input) {
+        return Ok(input.to_string());
+    }
+
+    let matches = data
+        .todos
+        .iter()
+        .filter(|todo| todo.id.starts_with(input))
+        .collect::<Vec<_>>();
+
+    match matches.as_slice() {
+        [todo] => Ok(todo.id.clone()),
+        [] => Err(format!("no todo matches {input}")),
+        _ => Err(format!(
+            "{input} matches several todos, use a longer prefix"
+        )),
+    }
+}
+
+/// List id, or a case-insensitive list name.
+pub fn resolve_list_id(data: &AppData, input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let lists = &data.settings.lists;
+
+    lists
+        .iter()
+        .find(|list| list.id == input)
+        .or_else(|| {
+            lists
+                .iter()
+                .find(|list| list.name.to_lowercase() == input.to_lowercase())
+        })
+        .map(|list| list.id.clone())
+        .ok_or_else(|| format!("no list named {input}"))
+}
+
+/// Todos in display order (lists, then manual order within each parent) with
+/// their nesting depth.
+pub fn ordered_todos(data: &AppData) -> Vec<(&Todo, usize)> {
+    let mut children: HashMap<Option<&str>, Vec<&Todo>> = HashMap::new();
+    let known_ids = data
+        .todos
+        .iter()
+        .map(|todo| todo.id.as_str())
+        .collect::<HashSet<_>>();
+
+    for todo in &data.todos {
+        let parent = todo
+            .parent_id
+            .as_deref()
+            .filter(|parent_id| known_ids.contains(parent_id));
+        children.entry(parent).or_default().push(todo);
+    }
+
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|todo| (todo.sort_index.unwrap_or(i64::MAX), todo.created_at));
+    }
+
+    let mut ordered = Vec::with_capacity(data.todos.len());
+    let roots = children.get(&None).cloned().unwrap_or_default();
+
+    for list in &data.settings.lists {
+        let mut stack = roots
+            .iter()
+            .rev()
+            .filter(|todo| todo.list_id.as_deref() == Some(list.id.as_str()))
+            .map(|todo| (*todo, 0))
+            .collect::<Vec<_>>();
+
+        while let Some((todo, depth)) = stack.pop() {
+            if ordered.len() > data.todos.len() {
+                break;
+            }
+            ordered.push((todo, depth));
+
+            if let Some(nested) = children.get(&Some(todo.id.as_str())) {
+                stack.extend(nested.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+    }
+
+    ordered
+}
+
 fn default_auto_close_on_blur() -> bool {
     true
 }
 
 fn default_list_name() -> String {
-    "Mes tâches".to_string()
+    i18n::messages(&default_language())
+        .default_list_name
+        .to_string()
 }
 
 fn default_active_list_id() -> String {
@@ -214,6 +627,7 @@ fn default_lists() -> Vec<TodoList> {
         id: default_active_list_id(),
         name: default_list_name(),
         icon: None,
+        notification_sound: None,
         created_at: 0,
     }]
 }
@@ -225,7 +639,9 @@ fn default_global_shortcut() -> String {
 fn default_labels() -> Vec<TodoLabel> {
     vec![TodoLabel {
         id: "general".to_string(),
-        name: "Général".to_string(),
+        name: i18n::messages(&default_language())
+            .default_label_name
+            .to_string(),
         color: "slate".to_string(),
     }]
 }
@@ -238,6 +654,58 @@ fn default_enable_sound_effects() -> bool {
     true
 }
 
+fn default_script_hook_timeout_seconds() -> u32 {
+    DEFAULT_SCRIPT_HOOK_TIMEOUT_SECONDS
+}
+
+fn default_webhook_events() -> Vec<TodoEventKind> {
+    TodoEventKind::ALL.to_vec()
+}
+
+/// Skips event names this version does not know, e.g. from a typo in a
+/// hand-edited file.
+fn deserialize_known_events<'de, D>(deserializer: D) -> Result<Vec<TodoEventKind>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = Vec::<Value>::deserialize(deserializer)?;
+
+    Ok(names
+        .into_iter()
+        .filter_map(|name| match TodoEventKind::deserialize(&name) {
+            Ok(event) => Some(event),
+            Err(_) => {
+                log::warn!("ignoring unknown event {name}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Drops the entries that fail to deserialize instead of failing the whole
+/// data file, which would then be reset with defaults.
+fn deserialize_valid_entries<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let Value::Array(entries) = Value::deserialize(deserializer)? else {
+        log::warn!("ignoring settings entries that are not a list");
+        return Ok(Vec::new());
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match serde_json::from_value(entry) {
+            Ok(entry) => Some(entry),
+            Err(error) => {
+                log::warn!("dropping invalid settings entry: {error}");
+                None
+            }
+        })
+        .collect())
+}
+
 fn default_true() -> bool {
     true
 }
@@ -254,6 +722,22 @@ fn default_language() -> String {
     "auto".to_string()
 }
 
+fn default_urgent_repeat_minutes() -> u32 {
+    DEFAULT_URGENT_REPEAT_MINUTES
+}
+
+fn default_high_renotify_minutes() -> u32 {
+    DEFAULT_HIGH_RENOTIFY_MINUTES
+}
+
+fn default_daily_summary_time() -> String {
+    DEFAULT_DAILY_SUMMARY_TIME.to_string()
+}
+
+fn default_http_api_port() -> u16 {
+    DEFAULT_HTTP_API_PORT
+}
+
 pub fn normalize_shortcut(value: &str) -> String {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -263,6 +747,92 @@ pub fn normalize_shortcut(value: &str) -> String {
     }
 }
 
+/// Accepts `H:MM` or `HH:MM` (24h) and returns it zero-padded, falling back
+/// to the default summary time for anything else.
+pub fn normalize_daily_summary_time(value: &str) -> String {
+    let parsed = value.trim().split_once(':').and_then(|(hours, minutes)| {
+        let hours = hours.parse::<u32>().ok().filter(|hours| *hours < 24)?;
+        let minutes = minutes
+            .parse::<u32>()
+            .ok()
+            .filter(|minutes| *minutes < 60)?;
+        Some((hours, minutes))
+    });
+
+    match parsed {
+        Some((hours, minutes)) => format!("{hours:02}:{minutes:02}"),
+        None => default_daily_summary_time(),
+    }
+}
+
+/// Restores the default port when unset and generates a token the first
+/// time the API is enabled, so that it never runs unauthenticated.
+pub fn normalize_http_api(mut settings: HttpApiSettings) -> HttpApiSettings {
+    if settings.port == 0 {
+        settings.port = DEFAULT_HTTP_API_PORT;
+    }
+
+    settings.token = settings.token.trim().to_string();
+    if settings.enabled && settings.token.is_empty() {
+        settings.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+
+    settings
+}
+
+/// Drops webhooks without an http(s) URL and gives the others a unique id.
+pub fn normalize_webhooks(webhooks: Vec<WebhookSettings>) -> Vec<WebhookSettings> {
+    let mut used_ids = HashSet::new();
+
+    webhooks
+        .into_iter()
+        .filter_map(|mut webhook| {
+            webhook.url = webhook.url.trim().to_string();
+            let url = webhook.url.to_ascii_lowercase();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return None;
+            }
+
+            webhook.id = webhook.id.trim().to_string();
+            if webhook.id.is_empty() || used_ids.contains(&webhook.id) {
+                webhook.id = uuid::Uuid::new_v4().to_string();
+            }
+            used_ids.insert(webhook.id.clone());
+
+            let mut seen = HashSet::new();
+            webhook.events.retain(|event| seen.insert(*event));
+            Some(webhook)
+        })
+        .collect()
+}
+
+/// Drops hooks without a command, gives the others a unique id and keeps
+/// timeouts between one second and ten minutes.
+pub fn normalize_script_hooks(hooks: Vec<ScriptHookSettings>) -> Vec<ScriptHookSettings> {
+    let mut used_ids = HashSet::new();
+
+    hooks
+        .into_iter()
+        .filter_map(|mut hook| {
+            hook.command = hook.command.trim().to_string();
+            if hook.command.is_empty() {
+                return None;
+            }
+
+            hook.id = hook.id.trim().to_string();
+            if hook.id.is_empty() || used_ids.contains(&hook.id) {
+                hook.id = uuid::Uuid::new_v4().to_string();
+            }
+            used_ids.insert(hook.id.clone());
+
+            hook.timeout_seconds = hook
+                .timeout_seconds
+                .clamp(1, MAX_SCRIPT_HOOK_TIMEOUT_SECONDS);
+            Some(hook)
+        })
+        .collect()
+}
+
 fn normalize_label_color(value: &str) -> String {
     let normalized = value.trim().to_ascii_lowercase();
     match normalized.as_str() {
@@ -280,7 +850,9 @@ fn normalize_name(value: &str, fallback: &str) -> String {
     }
 }
 
-fn normalize_data(mut data: AppData) -> AppData {
+/// Fills in missing lists and labels and repairs references to them.
+pub(crate) fn normalize_data(mut data: AppData) -> AppData {
+    let messages = i18n::messages(&data.settings.language);
     let mut lists = data.settings.lists.clone();
 
     if lists.is_empty() {
@@ -288,21 +860,22 @@ fn normalize_data(mut data: AppData) -> AppData {
             .settings
             .legacy_list_name
             .as_deref()
-            .map(|name| normalize_name(name, &default_list_name()))
-            .unwrap_or_else(default_list_name);
+            .map(|name| normalize_name(name, messages.default_list_name))
+            .unwrap_or_else(|| messages.default_list_name.to_string());
 
         lists.push(TodoList {
             id: default_active_list_id(),
             name: legacy_name,
             icon: None,
+            notification_sound: None,
             created_at: now_millis(),
         });
     } else {
         for (index, list) in lists.iter_mut().enumerate() {
             let fallback = if index == 0 {
-                "Mes tâches"
+                messages.default_list_name
             } else {
-                "Nouvelle liste"
+                messages.new_list_name
             };
             list.name = normalize_name(&list.name, fallback);
         }
@@ -369,12 +942,17 @@ fn normalize_data(mut data: AppData) -> AppData {
     data.settings.lists = lists;
     data.settings.active_list_id = active_list_id;
     data.settings.global_shortcut = normalize_shortcut(&data.settings.global_shortcut);
+    data.settings.daily_summary.time =
+        normalize_daily_summary_time(&data.settings.daily_summary.time);
+    data.settings.http_api = normalize_http_api(data.settings.http_api);
+    data.settings.webhooks = normalize_webhooks(data.settings.webhooks);
+    data.settings.script_hooks = normalize_script_hooks(data.settings.script_hooks);
     data.settings.labels = labels;
     data.settings.legacy_list_name = None;
     data
 }
 
-fn storage_path(app: &AppHandle) -> Result<PathBuf, String> {
+pub fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let app_dir = app
         .path()
         .app_data_dir()
@@ -383,25 +961,136 @@ fn storage_path(app: &AppHandle) -> Result<PathBuf, String> {
     fs::create_dir_all(&app_dir)
         .map_err(|error| format!("failed to create appDataDir directory: {error}"))?;
 
-    Ok(app_dir.join(STORAGE_FILE_NAME))
+    Ok(app_dir)
+}
+
+/// Same directory as Tauri's `appDataDir`, resolved without an `AppHandle` so
+/// that the command-line client can find `todos.json`.
+pub fn standalone_data_dir() -> Result<PathBuf, String> {
+    let app_dir = dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| String::from("failed to resolve the user data directory"))?;
+
+    fs::create_dir_all(&app_dir)
+        .map_err(|error| format!("failed to create data directory: {error}"))?;
+
+    Ok(app_dir)
+}
+
+fn open_lock_file(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .map_err(|error| format!("failed to open lock file {}: {error}", path.display()))
+}
+
+/// Exclusive advisory lock held while `todos.json` is read-modified-written,
+/// shared by the app and the command-line client. Released on drop.
+pub struct DataLock {
+    _file: File,
+}
+
+impl DataLock {
+    pub fn acquire(data_dir: &Path) -> Result<Self, String> {
+        let path = data_dir.join(DATA_LOCK_FILE_NAME);
+        let file = open_lock_file(&path)?;
+        file.lock_exclusive()
+            .map_err(|error| format!("failed to lock {}: {error}", path.display()))?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Held by the running app for its whole lifetime so that other processes can
+/// tell whether it is running.
+pub struct InstanceLock {
+    _file: File,
+}
+
+/// Returns `None` when another process already holds the instance lock.
+pub fn try_acquire_instance_lock(data_dir: &Path) -> Result<Option<InstanceLock>, String> {
+    let path = data_dir.join(INSTANCE_LOCK_FILE_NAME);
+    let file = open_lock_file(&path)?;
+    let acquired = file
+        .try_lock_exclusive()
+        .map_err(|error| format!("failed to lock {}: {error}", path.display()))?;
+
+    Ok(acquired.then_some(InstanceLock { _file: file }))
+}
+
+/// Takes the instance lock for the app, waiting while command-line clients
+/// hold it for a moment to check whether the app is running. The app must
+/// not run without it, or these clients would write `todos.json` behind it.
+pub fn acquire_app_instance_lock(app: &AppHandle) -> Result<InstanceLock, String> {
+    let data_dir = app_data_dir(app)?;
+    let deadline = Instant::now() + INSTANCE_LOCK_TIMEOUT;
+
+    loop {
+        if let Some(lock) = try_acquire_instance_lock(&data_dir)? {
+            return Ok(lock);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "another process holds {}",
+                data_dir.join(INSTANCE_LOCK_FILE_NAME).display()
+            ));
+        }
+        thread::sleep(INSTANCE_LOCK_RETRY_INTERVAL);
+    }
+}
+
+pub fn is_app_running(data_dir: &Path) -> Result<bool, String> {
+    Ok(try_acquire_instance_lock(data_dir)?.is_none())
+}
+
+/// Deserializes and normalizes the content of a storage file.
+pub fn parse_data(raw: &str) -> Result<AppData, serde_json::Error> {
+    serde_json::from_str::<AppData>(raw).map(normalize_data)
+}
+
+/// Reads a storage file from disk, `Ok(None)` when it does not exist yet.
+pub fn read_data_file(path: &Path) -> Result<Option<AppData>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(path)
+        .map_err(|error| format!("failed to read storage file {}: {error}", path.display()))?;
+
+    parse_data(&raw).map(Some).map_err(|error| {
+        format!(
+            "failed to deserialize storage file {}: {error}",
+            path.display()
+        )
+    })
+}
+
+pub fn write_data_file(path: &Path, data: &AppData) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(data)
+        .map_err(|error| format!("failed to serialize storage payload: {error}"))?;
+
+    fs::write(path, payload)
+        .map_err(|error| format!("failed to write storage file {}: {error}", path.display()))
 }
 
 pub fn load_or_create(app: &AppHandle) -> Result<AppData, String> {
-    let path = storage_path(app)?;
+    let app_dir = app_data_dir(app)?;
+    let path = app_dir.join(STORAGE_FILE_NAME);
+    let _lock = DataLock::acquire(&app_dir)?;
 
     if !path.exists() {
         let data = AppData::default();
-        persist(app, &data)?;
+        write_data_file(&path, &data)?;
         return Ok(data);
     }
 
     let raw = fs::read_to_string(&path)
         .map_err(|error| format!("failed to read storage file {}: {error}", path.display()))?;
 
-    match serde_json::from_str::<AppData>(&raw) {
-        Ok(data) => {
-            let normalized = normalize_data(data);
-            persist(app, &normalized)?;
+    match parse_data(&raw) {
+        Ok(normalized) => {
+            write_data_file(&path, &normalized)?;
             Ok(normalized)
         }
         Err(error) => {
@@ -411,20 +1100,38 @@ pub fn load_or_create(app: &AppHandle) -> Result<AppData, String> {
             );
 
             let data = AppData::default();
-            persist(app, &data)?;
+            write_data_file(&path, &data)?;
             Ok(data)
         }
     }
 }
 
 pub fn persist(app: &AppHandle, data: &AppData) -> Result<(), String> {
-    let path = storage_path(app)?;
+    let app_dir = app_data_dir(app)?;
+    {
+        let _lock = DataLock::acquire(&app_dir)?;
+        write_data_file(&app_dir.join(STORAGE_FILE_NAME), data)?;
+    }
 
-    let payload = serde_json::to_string_pretty(data)
-        .map_err(|error| format!("failed to serialize storage payload: {error}"))?;
+    crate::ipc::publish_change(app, data);
+    crate::events::publish_change(app, data);
+    #[cfg(target_os = "linux")]
+    crate::dbus::publish_change(app, data);
+    Ok(())
+}
 
-    fs::write(&path, payload)
-        .map_err(|error| format!("failed to write storage file {}: {error}", path.display()))
+/// Payload of `state-changed`, sent to every webview after a save.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChanged<'a> {
+    pub revision: u64,
+    pub data: &'a AppData,
+}
+
+pub fn broadcast_state(app: &AppHandle, revision: u64, data: &AppData) {
+    if let Err(error) = app.emit(STATE_CHANGED_EVENT, StateChanged { revision, data }) {
+        log::warn!("failed to broadcast state revision {revision}: {error}");
+    }
 }
 
 pub fn now_millis() -> i64 {
@@ -433,3 +1140,69 @@ pub fn now_millis() -> i64 {
         .expect("system time drifted before unix epoch")
         .as_millis() as i64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_todos_when_hooks_are_invalid() {
+        let raw = r#"{
+            "settings": {
+                "webhooks": [
+                    { "url": "https://example.com/hook", "events": ["created", "todo.finished"] },
+                    { "url": 42 }
+                ],
+                "scriptHooks": [
+                    { "command": "/usr/bin/true", "event": "created" },
+                    { "command": "/usr/bin/true", "event": "finished" },
+                    { "command": "/usr/bin/true" }
+                ]
+            },
+            "todos": [
+                { "id": "a", "title": "Still here", "createdAt": 1000, "completedAt": null, "reminderAt": null }
+            ]
+        }"#;
+
+        let data = parse_data(raw).expect("data file stays readable");
+        assert_eq!(data.todos.len(), 1);
+        assert_eq!(data.todos[0].title, "Still here");
+        assert_eq!(data.settings.webhooks.len(), 1);
+        assert_eq!(
+            data.settings.webhooks[0].events,
+            vec![TodoEventKind::Created]
+        );
+        assert_eq!(data.settings.script_hooks.len(), 1);
+    }
+
+    #[test]
+    fn parses_dates_and_times() {
+        assert_eq!(parse_date_time("1700000000000"), Some(1_700_000_000_000));
+        assert_eq!(
+            parse_date_time("2026-03-02T09:00:00Z"),
+            Some(1_772_442_000_000)
+        );
+
+        let local = |text: &str| {
+            let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").expect("valid");
+            Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|date_time| date_time.timestamp_millis())
+        };
+        assert_eq!(
+            parse_date_time(" 2026-03-02 18:30 "),
+            local("2026-03-02 18:30")
+        );
+        assert_eq!(
+            parse_date_time("2026-03-02T18:30"),
+            local("2026-03-02 18:30")
+        );
+        assert_eq!(parse_date_time("2026-03-02"), local("2026-03-02 09:00"));
+
+        assert_eq!(parse_date_time("2026"), None);
+        assert_eq!(parse_date_time("1700000000"), None);
+        assert_eq!(parse_date_time("next week"), None);
+        assert_eq!(parse_date_time("2026-13-01"), None);
+    }
+}
@@ -1,22 +1,48 @@
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Manager, Wry};
 
+use crate::i18n;
+use crate::storage::AppState;
 use crate::window;
 
-pub fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
+const TRAY_ID: &str = "main";
+
+fn build_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<Wry>> {
+    let language = app
+        .state::<AppState>()
+        .data
+        .lock()
+        .map(|guard| guard.settings.language.clone())
+        .unwrap_or_default();
+    let messages = i18n::messages(&language);
+
     let show_main_item =
-        MenuItemBuilder::with_id("show_main", "Afficher fenêtre principale").build(app)?;
+        MenuItemBuilder::with_id("show_main", messages.tray_show_main).build(app)?;
     let toggle_overlay_item =
-        MenuItemBuilder::with_id("toggle_overlay", "Afficher / Masquer overlay").build(app)?;
-    let quit_item = MenuItemBuilder::with_id("quit", "Quitter").build(app)?;
+        MenuItemBuilder::with_id("toggle_overlay", messages.tray_toggle_overlay).build(app)?;
+    let quit_item = MenuItemBuilder::with_id("quit", messages.tray_quit).build(app)?;
 
-    let menu = MenuBuilder::new(app)
+    MenuBuilder::new(app)
         .item(&show_main_item)
         .item(&toggle_overlay_item)
         .item(&quit_item)
-        .build()?;
+        .build()
+}
+
+/// Rebuilds the tray menu, e.g. after `Settings.language` changed.
+pub fn refresh_menu(app: &tauri::AppHandle) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app)?))?;
+    }
+
+    Ok(())
+}
+
+pub fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
 
-    let mut tray_builder = TrayIconBuilder::new()
+    let mut tray_builder = TrayIconBuilder::with_id(TRAY_ID)
         .icon_as_template(cfg!(target_os = "macos"))
         .tooltip("BlinkDo")
         .menu(&menu)
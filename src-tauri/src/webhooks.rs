@@ -0,0 +1,273 @@
+//! Outgoing webhooks: a JSON `POST` to every enabled `settings.webhooks`
+//! entry subscribed to a todo event. Deliveries go through a bounded queue
+//! drained by one worker thread, are retried with a growing delay while the
+//! webhook is still enabled, and are recorded in an in-memory delivery log
+//! (`get_webhook_deliveries`).
+//!
+//! The body is the versioned `EventPayload` plus a `deliveryId`.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::events::{EventPayload, TodoEvent};
+use crate::storage::{now_millis, AppState, Settings, TodoEventKind};
+
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+// Delay before each retry; a delivery is attempted at most once more than
+// there are entries.
+const RETRY_DELAYS_SECONDS: [u64; 3] = [10, 60, 300];
+const DELIVERY_LOG_SIZE: usize = 100;
+// Deliveries waiting beyond this are dropped with a warning.
+const QUEUE_SIZE: usize = 256;
+
+/// Queue feeding the delivery worker, and the most recent attempts.
+#[derive(Default)]
+pub struct WebhookQueue {
+    sender: Mutex<Option<SyncSender<Delivery>>>,
+    log: Mutex<VecDeque<DeliveryRecord>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryRecord {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub url: String,
+    pub event: TodoEventKind,
+    pub todo_id: String,
+    pub attempt: u32,
+    pub attempted_at: i64,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub delivered: bool,
+}
+
+struct Delivery {
+    id: String,
+    webhook_id: String,
+    url: String,
+    event: TodoEventKind,
+    todo_id: String,
+    body: String,
+    attempt: u32,
+    due_at: Instant,
+}
+
+/// Starts the delivery worker. Events raised before are dropped.
+pub fn start_worker(app: AppHandle) {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+
+    if let Ok(mut queue_sender) = app.state::<WebhookQueue>().sender.lock() {
+        *queue_sender = Some(sender);
+    }
+
+    thread::spawn(move || run_worker(app, receiver));
+}
+
+/// Queues one delivery per event and subscribed webhook.
+pub fn enqueue(app: &AppHandle, settings: &Settings, events: &[TodoEvent]) {
+    let Some(queue) = app.try_state::<WebhookQueue>() else {
+        return;
+    };
+    let Ok(sender) = queue.sender.lock() else {
+        return;
+    };
+    let Some(sender) = sender.as_ref() else {
+        return;
+    };
+
+    for webhook in settings.webhooks.iter().filter(|webhook| webhook.enabled) {
+        for event in events
+            .iter()
+            .filter(|event| webhook.events.contains(&event.kind))
+        {
+            let id = uuid::Uuid::new_v4().to_string();
+            let mut payload = EventPayload::new(event, settings);
+            payload.delivery_id = Some(id.clone());
+            let body = match serde_json::to_string(&payload) {
+                Ok(body) => body,
+                Err(error) => {
+                    log::error!("failed to serialize webhook payload: {error}");
+                    continue;
+                }
+            };
+
+            let delivery = Delivery {
+                id,
+                webhook_id: webhook.id.clone(),
+                url: webhook.url.clone(),
+                event: event.kind,
+                todo_id: event.todo.id.clone(),
+                body,
+                attempt: 1,
+                due_at: Instant::now(),
+            };
+            match sender.try_send(delivery) {
+                Ok(()) => {}
+                Err(TrySendError::Full(delivery)) => log::warn!(
+                    "webhook {} dropped {} for {}, too many deliveries are waiting",
+                    delivery.webhook_id,
+                    delivery.event.name(),
+                    delivery.todo_id
+                ),
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+        }
+    }
+}
+
+/// Most recent delivery attempts, newest first.
+#[tauri::command]
+pub fn get_webhook_deliveries(queue: State<'_, WebhookQueue>) -> Vec<DeliveryRecord> {
+    queue
+        .log
+        .lock()
+        .map(|log| log.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn run_worker(app: AppHandle, receiver: Receiver<Delivery>) {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .user_agent(format!("BlinkDo/{}", app.package_info().version))
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => {
+            log::error!("failed to create webhook client: {error}");
+            return;
+        }
+    };
+    let mut pending: Vec<Delivery> = Vec::new();
+
+    loop {
+        let next = pending.iter().map(|delivery| delivery.due_at).min();
+        let received = match next {
+            Some(due_at) => receiver.recv_timeout(due_at.saturating_duration_since(Instant::now())),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok(delivery) => pending.push(delivery),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = pending
+            .drain(..)
+            .partition(|delivery| delivery.due_at <= now);
+        pending = waiting;
+
+        for delivery in due {
+            if !is_subscribed(&app, &delivery) {
+                log::info!(
+                    "webhook {} dropped {} for {}, it was disabled or removed",
+                    delivery.webhook_id,
+                    delivery.event.name(),
+                    delivery.todo_id
+                );
+                continue;
+            }
+            if let Some(retry) = deliver(&app, &client, delivery) {
+                pending.push(retry);
+            }
+        }
+    }
+}
+
+/// Whether the webhook of `delivery` is still enabled, with the same URL and
+/// subscribed to its event, in the current settings.
+fn is_subscribed(app: &AppHandle, delivery: &Delivery) -> bool {
+    let state = app.state::<AppState>();
+    let Ok(data) = state.data.lock() else {
+        return false;
+    };
+
+    data.settings.webhooks.iter().any(|webhook| {
+        webhook.id == delivery.webhook_id
+            && webhook.enabled
+            && webhook.url == delivery.url
+            && webhook.events.contains(&delivery.event)
+    })
+}
+
+/// Sends `delivery` once and returns it again if it should be retried.
+fn deliver(app: &AppHandle, client: &reqwest::Client, mut delivery: Delivery) -> Option<Delivery> {
+    let request = client
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header("X-BlinkDo-Event", delivery.event.name())
+        .header("X-BlinkDo-Delivery", &delivery.id)
+        .body(delivery.body.clone())
+        .send();
+
+    let (status, error) = match tauri::async_runtime::block_on(request) {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("unexpected status {}", response.status())),
+        ),
+        Err(error) => (None, Some(error.to_string())),
+    };
+    let delivered = error.is_none();
+
+    match &error {
+        None => log::info!(
+            "webhook {} delivered {} for {}",
+            delivery.webhook_id,
+            delivery.event.name(),
+            delivery.todo_id
+        ),
+        Some(error) => log::warn!(
+            "webhook {} failed to deliver {} for {} (attempt {}): {error}",
+            delivery.webhook_id,
+            delivery.event.name(),
+            delivery.todo_id,
+            delivery.attempt
+        ),
+    }
+
+    record(
+        app,
+        DeliveryRecord {
+            delivery_id: delivery.id.clone(),
+            webhook_id: delivery.webhook_id.clone(),
+            url: delivery.url.clone(),
+            event: delivery.event,
+            todo_id: delivery.todo_id.clone(),
+            attempt: delivery.attempt,
+            attempted_at: now_millis(),
+            status,
+            error,
+            delivered,
+        },
+    );
+
+    if delivered {
+        return None;
+    }
+
+    let delay = RETRY_DELAYS_SECONDS.get(delivery.attempt as usize - 1)?;
+    delivery.attempt += 1;
+    delivery.due_at = Instant::now() + Duration::from_secs(*delay);
+    Some(delivery)
+}
+
+fn record(app: &AppHandle, entry: DeliveryRecord) {
+    let queue = app.state::<WebhookQueue>();
+    let Ok(mut log) = queue.log.lock() else {
+        return;
+    };
+
+    if log.len() == DELIVERY_LOG_SIZE {
+        log.pop_front();
+    }
+    log.push_back(entry);
+}
@@ -29,7 +29,32 @@ import { useTodoStore } from '@/store/use-todo-store'
 import { useUpdateStore } from '@/store/use-update-store'
 import { setWindowWidth, setOverlayVisorEffect, isOverlayWindow } from '@/lib/tauri'
 import { cn } from '@/lib/utils'
-import type { SortMode, Todo, TodoPriority } from '@/types/todo'
+import type { SortMode, StateChange, Todo, TodoPriority } from '@/types/todo'
+
+// Délai maximal accepté par setTimeout (~24,8 jours)
+const MAX_TIMEOUT_MS = 2_147_483_647
+
+// Tâches différées : celles dont la date de début, ou celle d'un parent, n'est
+// pas encore atteinte (même règle que classify_availability côté Rust)
+function deferredTodoIds(todos: Todo[], now: number): Set<string> {
+  const todoById = new Map(todos.map((todo) => [todo.id, todo]))
+  const deferredIds = new Set<string>()
+
+  for (const todo of todos) {
+    const visited = new Set<string>()
+    let current: Todo | undefined = todo
+    while (current && !visited.has(current.id)) {
+      visited.add(current.id)
+      if (typeof current.startAt === 'number' && current.startAt > now) {
+        deferredIds.add(todo.id)
+        break
+      }
+      current = current.parentId ? todoById.get(current.parentId) : undefined
+    }
+  }
+
+  return deferredIds
+}
 
 function compareTodoOrder(
   left: Todo,
@@ -148,6 +173,8 @@ export default function App() {
     setAutostartEnabled,
     updateSettings,
     updateTodo,
+    applyStateChange,
+    resyncState,
   } = useTodoStore()
 
   const { checkForUpdate } = useUpdateStore()
@@ -283,6 +310,25 @@ export default function App() {
     }
   }, [hydrate, toast, t])
 
+  // Every save is broadcast to all windows, whichever window, script or
+  // integration made the change
+  useEffect(() => {
+    const unlisten = listen<StateChange>('state-changed', (event) => {
+      applyStateChange(event.payload)
+    })
+
+    // Events are not replayed, so catch up on whatever was missed
+    const handleFocus = () => {
+      void resyncState()
+    }
+    window.addEventListener('focus', handleFocus)
+
+    return () => {
+      void unlisten.then(fn => fn())
+      window.removeEventListener('focus', handleFocus)
+    }
+  }, [applyStateChange, resyncState])
+
   // Vérifier les mises à jour au démarrage
   useEffect(() => {
     if (hydrated) {
@@ -353,9 +399,37 @@ export default function App() {
     [effectiveLabelFilterId, favoritesOnly, priorityFilter, sortedTodos],
   )
 
+  // Heure de référence des tâches différées, avancée à la prochaine date de
+  // début pour les afficher dès qu'elles deviennent disponibles
+  const [now, setNow] = useState(() => Date.now())
+
+  useEffect(() => {
+    const nextStartAt = todos.reduce<number | undefined>(
+      (next, todo) =>
+        typeof todo.startAt === 'number' && todo.startAt > now && (next === undefined || todo.startAt < next)
+          ? todo.startAt
+          : next,
+      undefined,
+    )
+    if (nextStartAt === undefined) {
+      return
+    }
+
+    const timer = window.setTimeout(() => {
+      setNow(Date.now())
+    }, Math.min(nextStartAt - now, MAX_TIMEOUT_MS))
+    return () => window.clearTimeout(timer)
+  }, [now, todos])
+
+  const deferredIds = useMemo(() => deferredTodoIds(todos, now), [now, todos])
+
   const activeTodos = useMemo(
-    () => visibleTodos.filter((todo) => typeof todo.completedAt !== 'number'),
-    [visibleTodos],
+    () =>
+      visibleTodos
+        .filter((todo) => typeof todo.completedAt !== 'number')
+        // Tâches différées : masquées jusqu'à leur date de début
+        .filter((todo) => !deferredIds.has(todo.id)),
+    [deferredIds, visibleTodos],
   )
 
   const completedTodos = useMemo(
@@ -8,9 +8,10 @@ import {
   useRef,
   useState,
 } from 'react'
-import { AlertTriangle, CalendarClock, Check, ChevronDown, ChevronRight, Ellipsis, FileText, Plus, Star, Tags, X } from 'lucide-react'
+import { AlertTriangle, BellOff, CalendarClock, Check, ChevronDown, ChevronRight, Ellipsis, FileText, Plus, Star, Tags, X } from 'lucide-react'
 import { AnimatePresence, LayoutGroup, motion } from 'framer-motion'
 import { useTranslation } from 'react-i18next'
+import { listen } from '@tauri-apps/api/event'
 import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Checkbox } from '@/components/ui/checkbox'
@@ -30,6 +31,7 @@ import { Textarea } from '@/components/ui/textarea'
 import { ScrollArea } from '@/components/ui/scroll-area'
 import { Popover, PopoverContent, PopoverTrigger } from '@/components/ui/popover'
 import { DateTimePicker } from '@/components/ui/date-time-picker'
+import { acknowledgeReminder } from '@/lib/tauri'
 import { cn } from '@/lib/utils'
 import type { Todo, TodoLabel, TodoListMeta, TodoPriority } from '@/types/todo'
 import { useSoundEffects } from '@/hooks/useSoundEffects'
@@ -322,6 +324,7 @@ export function TodoList({
   const [draggingTodoId, setDraggingTodoId] = useState<string | null>(null)
   const [dropTargetTodoId, setDropTargetTodoId] = useState<string | null>(null)
   const [dropPosition, setDropPosition] = useState<'before' | 'after' | null>(null)
+  const [acknowledgedReminderIds, setAcknowledgedReminderIds] = useState<Set<string>>(() => new Set())
 
   const activeItems = useMemo(() => buildTodoWithDepth(activeTodos), [activeTodos])
   const completedItems = useMemo(() => buildTodoWithDepth(completedTodos), [completedTodos])
@@ -520,6 +523,28 @@ export function TodoList({
     setDateMode(null)
   }
 
+  // Escalating reminders repeat until acknowledged, by opening the todo or
+  // with the button shown next to it. Keyed by reminder time, as a new time
+  // starts over.
+  const reminderKey = (todo: Todo) => `${todo.id}:${todo.reminderAt}`
+
+  const isReminderDue = (todo: Todo) =>
+    !todo.completedAt &&
+    typeof todo.reminderAt === 'number' &&
+    todo.reminderAt <= Date.now() &&
+    !acknowledgedReminderIds.has(reminderKey(todo))
+
+  const acknowledgeDueReminder = (todo: Todo) => {
+    if (!isReminderDue(todo)) {
+      return
+    }
+
+    setAcknowledgedReminderIds((previous) => new Set(previous).add(reminderKey(todo)))
+    void acknowledgeReminder(todo.id).catch((error) => {
+      console.error('Failed to acknowledge reminder', error)
+    })
+  }
+
   const openTodoEditor = async (
     todo: Todo,
     options?: {
@@ -538,6 +563,7 @@ export function TodoList({
       }
     }
 
+    acknowledgeDueReminder(todo)
     setEditingId(todo.id)
     setDraft({
       title: todo.title,
@@ -551,6 +577,38 @@ export function TodoList({
     setDateMode(null)
   }
 
+  // Todo requested by a blinkdo://open link, kept until it shows up in the
+  // list (the backend switches lists first)
+  const pendingOpenTodoIdRef = useRef<string | null>(null)
+  const openPendingTodoRef = useRef<() => void>(() => {})
+  openPendingTodoRef.current = () => {
+    const pendingId = pendingOpenTodoIdRef.current
+    if (pendingId === null) {
+      return
+    }
+
+    const todo = activeTodoById.get(pendingId) ?? completedTodos.find((candidate) => candidate.id === pendingId)
+    if (todo) {
+      pendingOpenTodoIdRef.current = null
+      void openTodoEditor(todo)
+    }
+  }
+
+  useEffect(() => {
+    const unlisten = listen<string>('open-todo', (event) => {
+      pendingOpenTodoIdRef.current = event.payload
+      openPendingTodoRef.current()
+    })
+
+    return () => {
+      void unlisten.then(fn => fn())
+    }
+  }, [])
+
+  useEffect(() => {
+    openPendingTodoRef.current()
+  }, [activeTodoById, completedTodos])
+
   const onEditorBlur = (event: FocusEvent<HTMLDivElement>) => {
     const nextFocused = event.relatedTarget
     if (nextFocused && event.currentTarget.contains(nextFocused)) {
@@ -1193,6 +1251,21 @@ export function TodoList({
                           )}
                         </button>
 
+                        {(priority === 'urgent' || priority === 'high') && isReminderDue(todo) ? (
+                          <Button
+                            type="button"
+                            variant="ghost"
+                            size="icon"
+                            className="h-7 w-7 text-muted-foreground hover:text-foreground"
+                            onClick={() => {
+                              acknowledgeDueReminder(todo)
+                            }}
+                            aria-label={t('todo.acknowledgeReminder', { title: todo.title })}
+                          >
+                            <BellOff className="h-3.5 w-3.5" />
+                          </Button>
+                        ) : null}
+
                         <Button
                           type="button"
                           variant="ghost"
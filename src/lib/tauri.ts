@@ -1,6 +1,18 @@
 import { invoke } from '@tauri-apps/api/core'
 import { getCurrentWindow } from '@tauri-apps/api/window'
-import type { AppData, Settings, TodoPriority } from '@/types/todo'
+import type {
+  AppData,
+  ArchiveImport,
+  ArchiveMetadata,
+  ExportTodosOptions,
+  ImportReport,
+  ImportSource,
+  MergeStrategy,
+  Settings,
+  StateSince,
+  TodoPriority,
+  TodosByAvailability,
+} from '@/types/todo'
 
 export type UpdateInfo = {
   available: boolean
@@ -14,6 +26,10 @@ export async function loadState(): Promise<AppData> {
   return invoke<AppData>('load_state')
 }
 
+export async function loadStateSince(revision: number): Promise<StateSince> {
+  return invoke<StateSince>('load_state_since', { revision })
+}
+
 export async function addTodo(text: string): Promise<AppData> {
   return invoke<AppData>('add_todo', { text })
 }
@@ -34,6 +50,15 @@ export async function createTodo(
   })
 }
 
+// Une tâche par ligne : l'indentation crée des sous-tâches, puces et cases à cocher sont retirées
+export async function pasteTodos(
+  text: string,
+  listId?: string,
+  parentId?: string,
+): Promise<AppData> {
+  return invoke<AppData>('paste_todos', { text, listId: listId ?? null, parentId: parentId ?? null })
+}
+
 export async function updateTodo(
   id: string,
   title: string,
@@ -114,6 +139,13 @@ export async function setListIcon(id: string, icon: string | undefined): Promise
   return invoke<AppData>('set_list_icon', { id, icon: icon ?? null })
 }
 
+export async function setListNotificationSound(
+  id: string,
+  sound: string | undefined,
+): Promise<AppData> {
+  return invoke<AppData>('set_list_notification_sound', { id, sound: sound ?? null })
+}
+
 export async function setActiveList(id: string): Promise<AppData> {
   return invoke<AppData>('set_active_list', { id })
 }
@@ -150,6 +182,25 @@ export async function setTodoReminder(
   return invoke<AppData>('set_todo_reminder', { id, reminderAt: reminderAt ?? null })
 }
 
+export async function setTodoStart(
+  id: string,
+  startAt: number | undefined,
+): Promise<AppData> {
+  return invoke<AppData>('set_todo_start', { id, startAt: startAt ?? null })
+}
+
+export async function queryTodos(listId?: string): Promise<TodosByAvailability> {
+  return invoke<TodosByAvailability>('query_todos', { listId: listId ?? null })
+}
+
+export async function acknowledgeReminder(id: string): Promise<void> {
+  await invoke('acknowledge_reminder', { id })
+}
+
+export async function previewDailySummary(): Promise<string> {
+  return invoke<string>('preview_daily_summary')
+}
+
 export async function hideOverlay(): Promise<void> {
   await invoke('hide_overlay')
 }
@@ -170,6 +221,21 @@ export async function getDataFilePath(): Promise<string> {
   return invoke<string>('get_data_file_path')
 }
 
+// path doit être absolu
+export async function exportArchive(path: string): Promise<ArchiveMetadata> {
+  return invoke<ArchiveMetadata>('export_archive', { path })
+}
+
+// Sans stratégie de fusion, l'archive remplace toutes les données et réglages ;
+// avec dryRun, rien n'est enregistré et le rapport liste les conflits
+export async function importArchive(
+  path: string,
+  merge: MergeStrategy | null,
+  dryRun: boolean,
+): Promise<ArchiveImport> {
+  return invoke<ArchiveImport>('import_archive', { path, merge, dryRun })
+}
+
 export async function openDataFile(): Promise<void> {
   await invoke('open_data_file')
 }
@@ -206,3 +272,60 @@ export function isOverlayWindow(): boolean {
   const currentWindow = getCurrentWindow()
   return currentWindow.label === 'overlay'
 }
+
+// Sans listId, toutes les listes sont exportées
+export async function exportMarkdown(listId?: string): Promise<string> {
+  return invoke<string>('export_markdown', { listId: listId ?? null })
+}
+
+export async function importMarkdown(content: string, listId: string): Promise<AppData> {
+  return invoke<AppData>('import_markdown', { content, listId })
+}
+
+export async function exportIcal(listId?: string): Promise<string> {
+  return invoke<string>('export_ical', { listId: listId ?? null })
+}
+
+export async function importIcal(content: string, listId: string): Promise<AppData> {
+  return invoke<AppData>('import_ical', { content, listId })
+}
+
+export async function exportOpml(listId: string): Promise<string> {
+  return invoke<string>('export_opml', { listId })
+}
+
+export async function importOpml(content: string, listId: string): Promise<AppData> {
+  return invoke<AppData>('import_opml', { content, listId })
+}
+
+// Renvoie le nombre de tâches écrites
+export async function exportTodos(options: ExportTodosOptions): Promise<number> {
+  return invoke<number>('export_todos', { options })
+}
+
+// Avec dryRun, rien n'est enregistré : le rapport décrit ce qui serait importé
+export async function importTasks(
+  source: ImportSource,
+  content: string,
+  listId: string,
+  dryRun: boolean,
+): Promise<ImportReport> {
+  return invoke<ImportReport>('import_tasks', { source, content, listId, dryRun })
+}
+
+export async function exportTaskwarrior(listId?: string): Promise<string> {
+  return invoke<string>('export_taskwarrior', { listId: listId ?? null })
+}
+
+// Les tâches dont l'UUID existe déjà sont ignorées
+export async function importTaskwarrior(content: string, listId: string): Promise<AppData> {
+  return invoke<AppData>('import_taskwarrior', { content, listId })
+}
+
+export async function exportTodoTxt(listId?: string): Promise<string> {
+  return invoke<string>('export_todotxt', { listId: listId ?? null })
+}
+
+export async function importTodoTxt(content: string, listId: string): Promise<AppData> {
+  return invoke<AppData>('import_todotxt', { content, listId })
+}
@@ -5,6 +5,7 @@ import {
   createList as createListCommand,
   createTodo as createTodoCommand,
   deleteTodo as deleteTodoCommand,
+  loadStateSince,
   moveTodoToList as moveTodoToListCommand,
   reorderTodos as reorderTodosCommand,
   renameList as renameListCommand,
@@ -20,7 +21,7 @@ import {
   updateSettings as updateSettingsCommand,
 } from '@/lib/tauri'
 import { createStorageProvider, type StorageProvider, type StorageMode, type SyncStatus } from '@/lib/storage'
-import type { Settings, Todo, TodoPriority, ViewMode } from '@/types/todo'
+import type { AppData, Settings, StateChange, Todo, TodoPriority, ViewMode } from '@/types/todo'
 import { ENABLE_CLOUD_FEATURES } from '@/config/features'
 
 type TodoStore = {
@@ -30,6 +31,8 @@ type TodoStore = {
   todos: Todo[]
   settings: Settings
   view: ViewMode
+  // Dernière révision du backend reçue via `state-changed` (mode local)
+  revision: number
   // Storage management
   storageMode: StorageMode
   syncStatus: SyncStatus
@@ -43,6 +46,9 @@ type TodoStore = {
   signOut: () => Promise<void>
   // Data operations
   hydrate: () => Promise<void>
+  applyStateChange: (change: StateChange) => void
+  applyCommandResult: (data: AppData) => void
+  resyncState: () => Promise<void>
   setView: (view: ViewMode) => void
   createTodo: (payload: {
     title: string
@@ -124,6 +130,17 @@ const setStoredStorageMode = (mode: StorageMode) => {
   }
 }
 
+// Migration: ajouter soundSettings si manquant
+const withSoundSettings = (data: AppData): Settings => ({
+  ...data.settings,
+  soundSettings: data.settings.soundSettings ?? {
+    enabled: data.settings.enableSoundEffects ?? true,
+    onCreate: true,
+    onComplete: true,
+    onDelete: true,
+  },
+})
+
 export const useTodoStore = create<TodoStore>((set, get) => ({
   hydrated: false,
   loading: false,
@@ -131,6 +148,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
   todos: [],
   settings: defaultSettings,
   view: 'active',
+  revision: 0,
   storageMode: getStoredStorageMode(),
   syncStatus: 'idle',
   storageProvider: null,
@@ -252,22 +270,14 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
       // Load data
       const data = await provider.load()
       
-      // Migration: ajouter soundSettings si manquant
-      const settings = {
-        ...data.settings,
-        soundSettings: data.settings.soundSettings ?? {
-          enabled: data.settings.enableSoundEffects ?? true,
-          onCreate: true,
-          onComplete: true,
-          onDelete: true,
-        }
-      }
+      const settings = withSoundSettings(data)
       
       set({
         hydrated: true,
         loading: false,
         todos: data.todos,
         settings,
+        revision: data.revision ?? get().revision,
         syncStatus: provider.getSyncStatus(),
       })
 
@@ -290,6 +300,39 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
     }
   },
 
+  applyStateChange: ({ revision, data }) => {
+    if (get().storageMode !== 'local' || revision <= get().revision) {
+      return
+    }
+
+    set({ revision, todos: data.todos, settings: withSoundSettings(data), error: null })
+  },
+
+  // Réponse d'une commande Tauri : ignorée si un `state-changed` plus récent est déjà appliqué
+  applyCommandResult: (data) => {
+    if (data.revision !== undefined && data.revision < get().revision) {
+      return
+    }
+
+    set({
+      revision: data.revision ?? get().revision,
+      todos: data.todos,
+      settings: data.settings,
+      error: null,
+    })
+  },
+
+  resyncState: async () => {
+    if (get().storageMode !== 'local') {
+      return
+    }
+
+    const { revision, data } = await loadStateSince(get().revision)
+    if (data) {
+      set({ revision, todos: data.todos, settings: withSoundSettings(data), error: null })
+    }
+  },
+
   setView: (view) => set({ view }),
 
   createTodo: async ({ title, details, reminderAt, parentId, listId }) => {
@@ -305,7 +348,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
       // En mode local, utiliser les commandes Tauri existantes
       if (mode === 'local') {
         const data = await createTodoCommand(trimmedTitle, details, reminderAt, parentId, listId)
-        set({ todos: data.todos, settings: data.settings, error: null })
+        get().applyCommandResult(data)
       } else {
         // En mode cloud, utiliser le provider
         if (!provider) throw new Error('Storage provider not initialized')
@@ -353,7 +396,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
     try {
       if (mode === 'local') {
         const data = await updateTodoCommand(id, trimmedTitle, details, reminderAt)
-        set({ todos: data.todos, settings: data.settings, error: null })
+        get().applyCommandResult(data)
       } else {
         if (!provider) throw new Error('Storage provider not initialized')
 
@@ -384,7 +427,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setTodoCompletedCommand(id, completed)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -410,7 +453,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setTodoStarredCommand(id, starred)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -434,7 +477,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setTodoPriorityCommand(id, priority)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -458,7 +501,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setTodoLabelCommand(id, labelId)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -486,7 +529,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await reorderTodosCommand(listId, parentId, completed, orderedIds)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -516,7 +559,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await createListCommand(name)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -549,7 +592,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await renameListCommand(id, name)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -576,7 +619,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setListIconCommand(id, icon)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -603,7 +646,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setActiveListCommand(id)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -629,7 +672,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await deleteTodoCommand(id)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -653,7 +696,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await clearHistoryCommand()
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -677,7 +720,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await clearCompletedInListCommand(listId)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -701,7 +744,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await moveTodoToListCommand(id, listId)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -739,7 +782,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (storageMode === 'local') {
       const data = await updateSettingsCommand(mergedSettings)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!storageProvider) throw new Error('Storage provider not initialized')
 
@@ -758,7 +801,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setGlobalShortcutCommand(shortcut)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
@@ -784,7 +827,7 @@ export const useTodoStore = create<TodoStore>((set, get) => ({
 
     if (mode === 'local') {
       const data = await setAutostartEnabledCommand(enabled)
-      set({ todos: data.todos, settings: data.settings, error: null })
+      get().applyCommandResult(data)
     } else {
       if (!provider) throw new Error('Storage provider not initialized')
 
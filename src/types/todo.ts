@@ -13,6 +13,7 @@ export type TodoListMeta = {
   id: string
   name: string
   icon?: string
+  notificationSound?: string // Son des rappels de cette liste
   createdAt: number
 }
 
@@ -29,13 +30,69 @@ export type Todo = {
   createdAt: number
   completedAt?: number
   reminderAt?: number
+  startAt?: number // Masquée des vues tant que cette date n'est pas atteinte
+  updatedAt?: number // Dernière modification, absente tant que la tâche n'a pas été modifiée
 }
 
+export type TodoAvailability = 'available' | 'deferred' | 'completed'
+
+export type TodosByAvailability = Record<TodoAvailability, Todo[]>
+
 export type SoundSettings = {
   enabled: boolean // Master toggle pour tous les sons
   onCreate: boolean // Son lors de la création d'une tâche
   onComplete: boolean // Son lors de la complétion d'une tâche
   onDelete: boolean // Son lors de la suppression d'une tâche
+  onReminder?: boolean // Son des notifications de rappel
+}
+
+export type DailySummarySettings = {
+  enabled: boolean
+  time: string // HH:MM, heure locale
+  lastSentAt?: number // Dernier résumé affiché, géré par le backend
+}
+
+export type HttpApiSettings = {
+  enabled: boolean
+  port: number
+  token: string // généré à la première activation
+}
+
+export type TodoEventKind = 'created' | 'completed' | 'deleted' | 'reminder'
+
+export type WebhookSettings = {
+  id: string
+  url: string
+  events: TodoEventKind[]
+  enabled: boolean
+}
+
+export type ScriptHookSettings = {
+  id: string
+  event: TodoEventKind
+  command: string
+  args: string[]
+  timeoutSeconds: number
+  enabled: boolean
+}
+
+export type WebhookDelivery = {
+  deliveryId: string
+  webhookId: string
+  url: string
+  event: TodoEventKind
+  todoId: string
+  attempt: number
+  attemptedAt: number
+  status: number | null
+  error: string | null
+  delivered: boolean
+}
+
+export type ReminderEscalationSettings = {
+  urgentRepeatMinutes: number // 0 = pas de répétition
+  urgentShowOverlay: boolean
+  highRenotifyMinutes: number // 0 = pas de second rappel
 }
 
 export type Settings = {
@@ -52,11 +109,117 @@ export type Settings = {
   soundSettings: SoundSettings
   language: string
   enableOverlayBlur: boolean // Blur effect around overlay window (macOS only)
+  dailySummary?: DailySummarySettings
+  reminderEscalation?: ReminderEscalationSettings
+  notifyWhenAvailable?: boolean
+  httpApi?: HttpApiSettings
+  webhooks?: WebhookSettings[]
+  scriptHooks?: ScriptHookSettings[]
 }
 
 export type AppData = {
   settings: Settings
   todos: Todo[]
+  revision?: number // Révision de cette copie, envoyée avec les réponses des commandes
+}
+
+// Payload de `state-changed`, et réponse de `load_state_since`
+export type StateChange = {
+  revision: number
+  data: AppData
+}
+
+export type StateSince = {
+  revision: number
+  data: AppData | null // null si rien n'a changé depuis la révision donnée
+}
+
+// Options de `export_todos` (CSV ou JSON pour les rapports)
+export type ExportColumn =
+  | 'id'
+  | 'title'
+  | 'details'
+  | 'list'
+  | 'label'
+  | 'priority'
+  | 'starred'
+  | 'parentId'
+  | 'createdAt'
+  | 'completedAt'
+  | 'reminderAt'
+  | 'startAt'
+
+export type ExportTodosOptions = {
+  path: string // chemin absolu, remplacé s'il existe
+  format: 'csv' | 'json'
+  filter?: {
+    listId?: string
+    labelId?: string
+    completed?: boolean
+    dateField?: 'created' | 'completed' | 'reminder' | 'start' // date comparée à from/to
+    from?: number // inclus
+    to?: number // exclu
+  }
+  columns?: ExportColumn[] // toutes les colonnes si vide
+}
+
+// Import depuis d'autres gestionnaires de tâches (`import_tasks`)
+export type ImportSource =
+  | 'todoistCsv'
+  | 'todoistJson'
+  | 'outlookCsv'
+  | 'microsoftTodoJson'
+  | 'thingsJson'
+
+export type ImportReport = {
+  dryRun: boolean
+  todos: number
+  lists: { id: string; name: string; created: boolean; todos: number }[]
+  labelsCreated: string[]
+  warnings: string[] // ce qui n'a pas pu être repris (récurrences, dates illisibles…)
+}
+
+// Archive complète de l'application (`export_archive` / `import_archive`)
+export type ArchiveMetadata = {
+  appVersion: string
+  exportedAt: number
+  platform: string
+  dataFile: string | null // fichier de données d'origine
+  dataModifiedAt: number | null // dernière sauvegarde de ce fichier
+  todos: number
+  lists: number
+  labels: number
+}
+
+// Fusion par identifiant : en cas de conflit, garder la version locale, l'importée,
+// la plus récemment modifiée (tâches uniquement) ou les deux
+export type MergeStrategy = 'keepLocal' | 'keepIncoming' | 'newestWins' | 'duplicate'
+
+export type MergeConflict = {
+  kind: 'list' | 'label' | 'todo'
+  id: string
+  localName: string
+  incomingName: string
+  fields: string[] // champs qui diffèrent
+  localEditedAt: number | null
+  incomingEditedAt: number | null
+  resolution: 'keptLocal' | 'tookIncoming' | 'duplicated'
+  duplicateId: string | null // identifiant de la copie importée avec 'duplicate'
+}
+
+export type ArchiveImport = {
+  version: number // 0 pour un simple todos.json
+  metadata: ArchiveMetadata | null
+  dryRun: boolean
+  strategy: MergeStrategy | null // null si l'archive a remplacé les données
+  listsAdded: number
+  listsUpdated: number
+  labelsAdded: number
+  labelsUpdated: number
+  todosAdded: number
+  todosUpdated: number
+  conflicts: MergeConflict[]
+  warnings: string[] // enregistrements réparés ou ignorés lors de la validation
 }
 
 export type ViewMode = 'active' | 'history'